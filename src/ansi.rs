@@ -0,0 +1,203 @@
+use std::{iter::Peekable, mem, ops::Range, str::Chars};
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Parses a line containing ANSI escape sequences into a styled line. Sequences
+/// that don't set graphic attributes are removed.
+pub fn parse_line(s: &str) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut span = String::new();
+    let mut style = Style::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                if let Some(params) = parse_escape(&mut chars) {
+                    let new_style = apply_sgr(style, &params);
+                    if new_style != style && !span.is_empty() {
+                        spans.push(Span::styled(mem::take(&mut span), style));
+                    }
+                    style = new_style;
+                }
+            }
+            '\t' => span.push_str("    "),
+            _ if c.is_control() => (),
+            _ => span.push(c),
+        }
+    }
+    if !span.is_empty() {
+        spans.push(Span::styled(span, style));
+    }
+    Line::from(spans)
+}
+
+/// Styles of byte ranges of a string.
+pub type Styles = Vec<(Range<usize>, Style)>;
+
+/// Removes ANSI escape sequences from a string, returning the remaining text
+/// and the styles of its byte ranges that aren't unstyled.
+pub fn strip(s: &str) -> (String, Styles) {
+    let mut text = String::with_capacity(s.len());
+    let mut styles = Vec::new();
+    let mut style = Style::new();
+    let mut start = 0;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            text.push(c);
+            continue;
+        }
+        if let Some(params) = parse_escape(&mut chars) {
+            let new_style = apply_sgr(style, &params);
+            if new_style != style {
+                if style != Style::new() && start < text.len() {
+                    styles.push((start..text.len(), style));
+                }
+                start = text.len();
+                style = new_style;
+            }
+        }
+    }
+    if style != Style::new() && start < text.len() {
+        styles.push((start..text.len(), style));
+    }
+    (text, styles)
+}
+
+/// Skips an escape sequence, returning its parameters if it's a select graphic
+/// rendition sequence.
+fn parse_escape(chars: &mut Peekable<Chars>) -> Option<String> {
+    match chars.next()? {
+        // Control sequence
+        '[' => {
+            let mut params = String::new();
+            for c in chars.by_ref() {
+                match c {
+                    '\x40'..='\x7e' => return (c == 'm').then_some(params),
+                    _ => params.push(c),
+                }
+            }
+            None
+        }
+        // Operating system command, terminated by BEL or ST
+        ']' => {
+            while let Some(c) = chars.next() {
+                match c {
+                    '\x07' => break,
+                    '\x1b' if chars.next_if_eq(&'\\').is_some() => break,
+                    _ => (),
+                }
+            }
+            None
+        }
+        // Other sequences have intermediate bytes and a final byte, e.g. the
+        // character set designation `ESC ( B`
+        '\x20'..='\x2f' => {
+            while chars.next_if(|c| matches!(c, '\x20'..='\x2f')).is_some() {}
+            chars.next();
+            None
+        }
+        _ => None,
+    }
+}
+
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    let mut params = params
+        .split([';', ':'])
+        .map(|param| param.parse::<u8>().unwrap_or(0));
+    while let Some(param) = params.next() {
+        style = match param {
+            0 => Style::new(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 => style.add_modifier(Modifier::SLOW_BLINK),
+            6 => style.add_modifier(Modifier::RAPID_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            8 => style.add_modifier(Modifier::HIDDEN),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            25 => style.remove_modifier(Modifier::SLOW_BLINK | Modifier::RAPID_BLINK),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            28 => style.remove_modifier(Modifier::HIDDEN),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(Color::Indexed(param - 30)),
+            38 => style.fg(parse_color(&mut params)),
+            39 => style.fg(Color::Reset),
+            40..=47 => style.bg(Color::Indexed(param - 40)),
+            48 => style.bg(parse_color(&mut params)),
+            49 => style.bg(Color::Reset),
+            90..=97 => style.fg(Color::Indexed(param - 90 + 8)),
+            100..=107 => style.bg(Color::Indexed(param - 100 + 8)),
+            _ => style,
+        };
+    }
+    style
+}
+
+fn parse_color<I>(params: &mut I) -> Color
+where
+    I: Iterator<Item = u8>,
+{
+    match params.next() {
+        Some(5) => Color::Indexed(params.next().unwrap_or(0)),
+        Some(2) => Color::Rgb(
+            params.next().unwrap_or(0),
+            params.next().unwrap_or(0),
+            params.next().unwrap_or(0),
+        ),
+        _ => Color::Reset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Stylize;
+
+    #[test]
+    fn parse_line() {
+        assert_eq!(super::parse_line("plain"), Line::from("plain"));
+        assert_eq!(
+            super::parse_line("\x1b[1;31mred\x1b[0m \x1b[38;5;10mgreen\x1b[m"),
+            Line::from(vec![
+                Span::styled("red", Style::new().bold().fg(Color::Indexed(1))),
+                Span::from(" "),
+                Span::styled("green", Style::new().fg(Color::Indexed(10))),
+            ])
+        );
+        assert_eq!(
+            super::parse_line("\x1b[48;2;1;2;3ma\x1b[Kb\x1b]8;;url\x07c"),
+            Line::from(Span::styled("abc", Style::new().bg(Color::Rgb(1, 2, 3))))
+        );
+        assert_eq!(
+            super::parse_line("\x1b(B\x1b[mtext\x1b7"),
+            Line::from("text")
+        );
+    }
+
+    #[test]
+    fn strip() {
+        assert_eq!(super::strip("plain"), ("plain".to_string(), Vec::new()));
+        assert_eq!(
+            super::strip("\x1b[31mred\x1b[0m\t\x1b[1mbold\x1b[Kö\x1b[m"),
+            (
+                "red\tboldö".to_string(),
+                vec![
+                    (0..3, Style::new().fg(Color::Indexed(1))),
+                    (4..10, Style::new().bold()),
+                ]
+            )
+        );
+        assert_eq!(
+            super::strip("\x1b(B\x1b[mtext\x1b#8"),
+            ("text".to_string(), Vec::new())
+        );
+    }
+}
@@ -1,24 +1,46 @@
-use std::io;
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, IsTerminal, Write},
+    os::{
+        fd::{AsFd, AsRawFd},
+        unix::process::CommandExt,
+    },
+    process::{ExitCode, Stdio},
+    time::Duration,
+};
 
 use anyhow::Result;
 use crossterm::{
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        MouseEvent, MouseEventKind,
+    },
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{prelude::CrosstermBackend, DefaultTerminal, Terminal, TerminalOptions};
+use tokio::{
+    sync::{
+        mpsc::{self, UnboundedSender},
+        watch,
+    },
+    task::{self, JoinHandle},
+    time,
 };
-use ratatui::{DefaultTerminal, TerminalOptions};
-use tokio::sync::{mpsc, watch};
-use tokio::task;
 
 use crate::{
     config::Config,
     events::{handle_events, Message},
-    searcher::debounce_draws,
-    state::State,
+    item::Item,
+    searcher::{debounce_draws, Searcher},
+    state::{ExitStatus, State},
+    template::{self, Context, TempFiles, Template},
     tui::Tui,
     types::{
         action::{Action, InputAction},
         key::Key,
     },
+    utils,
 };
 
 pub struct App<'a> {
@@ -26,56 +48,201 @@ pub struct App<'a> {
     state: State,
     tui: Tui<'a>,
     terminal: DefaultTerminal,
+    sender: UnboundedSender<Message>,
+    // Task that reads terminal events, which is stopped while commands are
+    // executed so it doesn't take their input
+    events: JoinHandle<()>,
+    output: File,
+    accepted: Vec<Item>,
 }
 
 impl App<'_> {
-    pub async fn run() -> Result<()> {
+    pub async fn run() -> Result<ExitCode> {
         let (sender, mut receiver) = mpsc::unbounded_channel();
         let (draw_sender, draw_receiver) = watch::channel(());
+        // Removes temporary files however tez exits, except by becoming a command
+        let _temp_files = TempFiles;
 
         let config = Config::load()?;
+        if let Some(query) = config.filter() {
+            return App::filter(&config, query).await;
+        }
+        let mut output = App::redirect_stdout()?;
         let state = State::new()?;
-        let tui = Tui::new(draw_sender)?;
+        let mut tui = Tui::new(&config, draw_sender)?;
+        if let Some(exit_status) = App::auto_accept(&config, &mut tui, &mut output).await? {
+            return Ok(exit_status.into());
+        }
         let terminal = App::init_terminal(&config, &state)?;
+        let events = task::spawn(handle_events(sender.clone()));
+        task::spawn(debounce_draws(draw_receiver, sender.clone()));
         let mut app = App {
             config,
             state,
             tui,
             terminal,
+            sender,
+            events,
+            output,
+            accepted: Vec::new(),
         };
 
-        task::spawn(handle_events(sender.clone()));
-        task::spawn(debounce_draws(draw_receiver, sender));
-
         while app.state.running() {
             if app.state.should_draw() {
                 app.draw()?;
             }
-            match receiver.recv().await {
+            let message = match app.tui.redraw_interval() {
+                Some(interval) => time::timeout(interval, receiver.recv())
+                    .await
+                    .unwrap_or(Some(Action::Draw.into())),
+                None => receiver.recv().await,
+            };
+            match message {
                 Some(Message::Error(error)) => Err(error)?,
-                Some(Message::Action(action)) => app.handle_action(&action)?,
-                Some(Message::Key(key)) => app.handle_key(key)?,
+                Some(Message::Action(action)) => {
+                    app.handle_action(&action).await?;
+                }
+                Some(Message::Key(key)) => app.handle_key(key).await?,
+                Some(Message::Mouse(mouse)) => app.handle_mouse(mouse).await?,
+                Some(Message::Paste(text)) => app.tui.paste(&text),
+                Some(Message::Resize(width, height)) => app.handle_resize(width, height).await?,
                 None => break,
             }
         }
 
         app.restore_terminal()?;
-        Ok(())
+        app.write_accepted()?;
+        Ok(app.state.exit_status().into())
+    }
+
+    /// Prints every item that matches the query in the order they'd be shown
+    /// in the TUI.
+    async fn filter(config: &Config, query: &str) -> Result<ExitCode> {
+        let (draw_sender, _) = watch::channel(());
+        let format = config.item_format();
+        let context = Context {
+            query: query.to_string(),
+            delimiter: format.delimiter().map(str::to_string),
+            ..Context::default()
+        };
+        let mut searcher = Searcher::new(
+            config.source(),
+            &context,
+            format,
+            config.match_options(),
+            config.sort_options(),
+            draw_sender,
+        );
+        searcher.search(query);
+        searcher.finish().await;
+
+        let items = (0..searcher.result_count()).filter_map(|pos| searcher.result(pos));
+        let mut output = BufWriter::new(io::stdout().lock());
+        write_items(&mut output, items, &config.output_separator())?;
+
+        let exit_status = match searcher.result_count() {
+            0 => ExitStatus::NoMatch,
+            _ => ExitStatus::Accepted,
+        };
+        Ok(exit_status.into())
+    }
+
+    /// Duplicates stdout so accepted items can be written to it later, then
+    /// points stdout at the terminal if it's redirected so the TUI can still be
+    /// drawn when tez is used in a pipeline.
+    fn redirect_stdout() -> Result<File> {
+        let stdout = io::stdout();
+        let output = File::from(stdout.as_fd().try_clone_to_owned()?);
+        if !stdout.is_terminal() {
+            let tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+            // SAFETY: Both file descriptors are valid for the duration of the call
+            if unsafe { libc::dup2(tty.as_raw_fd(), stdout.as_raw_fd()) } == -1 {
+                Err(io::Error::last_os_error())?;
+            }
+        }
+        Ok(output)
     }
 
     fn init_terminal(config: &Config, state: &State) -> Result<DefaultTerminal> {
         let mut terminal = ratatui::init_with_options(TerminalOptions {
             viewport: config.viewport(state.terminal_size().1)?,
         });
+        App::enter_terminal(config, &mut terminal)?;
+        Ok(terminal)
+    }
+
+    fn enter_terminal(config: &Config, terminal: &mut DefaultTerminal) -> Result<()> {
         if !config.is_inline() {
             execute!(io::stdout(), EnterAlternateScreen)?;
         } else {
             terminal.clear()?;
         }
-        Ok(terminal)
+        execute!(io::stdout(), EnableBracketedPaste)?;
+        if config.mouse() {
+            execute!(io::stdout(), EnableMouseCapture)?;
+        }
+        Ok(())
+    }
+
+    /// Takes the terminal back after it was restored for a command, then
+    /// redraws everything.
+    fn resume_terminal(&mut self) -> Result<()> {
+        terminal::enable_raw_mode()?;
+        if self.config.is_inline() {
+            // The command's output may have moved the cursor
+            self.terminal = App::inline_terminal(&self.config, self.state.terminal_size().1)?;
+        }
+        App::enter_terminal(&self.config, &mut self.terminal)?;
+        self.terminal.clear()?;
+        self.draw_forced()
+    }
+
+    fn inline_terminal(config: &Config, height: u16) -> Result<DefaultTerminal> {
+        Ok(Terminal::with_options(
+            CrosstermBackend::new(io::stdout()),
+            TerminalOptions {
+                viewport: config.viewport(height)?,
+            },
+        )?)
+    }
+
+    /// Recreates the terminal if the viewport is inline, since the height of
+    /// inline viewports can't be changed, then redraws.
+    async fn handle_resize(&mut self, width: u16, height: u16) -> Result<()> {
+        self.state.set_terminal_size((width, height));
+        if self.config.is_inline() {
+            self.terminal.clear()?;
+            // Creating an inline terminal reads the cursor position, which
+            // the events task would take otherwise
+            self.stop_events().await;
+            let terminal = App::inline_terminal(&self.config, height);
+            self.start_events();
+            self.terminal = terminal?;
+            self.terminal.clear()?;
+        }
+        self.draw_forced()
+    }
+
+    /// Stops reading terminal events, so the terminal can be read by commands
+    /// or when it's recreated.
+    async fn stop_events(&mut self) {
+        self.events.abort();
+        let _ = (&mut self.events).await;
+        // Dropping the event stream wakes up the thread that polls for events.
+        // If it wasn't polling, the next poll is woken up instead, which makes
+        // reading the cursor position fail, so that poll happens here.
+        let _ = event::poll(Duration::ZERO);
+    }
+
+    fn start_events(&mut self) {
+        self.events = task::spawn(handle_events(self.sender.clone()));
     }
 
     fn restore_terminal(&mut self) -> Result<()> {
+        if self.config.mouse() {
+            execute!(io::stdout(), DisableMouseCapture)?;
+        }
+        execute!(io::stdout(), DisableBracketedPaste)?;
         ratatui::restore();
         if !self.config.is_inline() {
             execute!(io::stdout(), LeaveAlternateScreen)?
@@ -85,9 +252,16 @@ impl App<'_> {
         Ok(())
     }
 
+    fn write_accepted(&mut self) -> Result<()> {
+        let items = self.accepted.drain(..);
+        write_items(&mut self.output, items, &self.config.output_separator())
+    }
+
     fn draw(&mut self) -> Result<()> {
         self.terminal
             .draw(|frame| frame.render_widget(&mut self.tui, self.config.area(frame.area())))?;
+        // Items are selected while they're drawn, so the preview follows after
+        self.tui.update_preview();
         Ok(())
     }
 
@@ -97,22 +271,255 @@ impl App<'_> {
         Ok(())
     }
 
-    fn handle_action(&mut self, action: &Action) -> Result<()> {
+    /// Handles an action and returns whether it succeeded, so the rest of a
+    /// chain can be skipped if it didn't.
+    async fn handle_action(&mut self, action: &Action) -> Result<bool> {
         match action {
-            Action::Exit => self.state.exit(),
+            Action::Exit => self.state.exit(ExitStatus::Aborted),
+            Action::Accept => self.accept(),
             Action::Draw => self.draw_forced()?,
-            Action::Tui(action) => self.tui.handle_action(action),
+            Action::Execute(command) => return self.execute(command).await,
+            Action::ExecuteSilent(command) => return Ok(self.execute_silent(command).await),
+            Action::Become(command) => return self.become_command(command).await,
+            // Steps run until one fails. Steps after tez stops running have
+            // nothing left to act on, so they're skipped as well.
+            Action::Chain(actions) => {
+                for action in actions {
+                    if !Box::pin(self.handle_action(action)).await? {
+                        return Ok(false);
+                    }
+                    if !self.state.running() {
+                        break;
+                    }
+                }
+            }
+            Action::Tui(action) => return Ok(self.tui.handle_action(action)),
         }
-        Ok(())
+        Ok(true)
+    }
+
+    /// Replaces the placeholders of a command, unless they refer to items
+    /// that aren't there, in which case the command isn't run, like in fzf.
+    fn expand(&self, command: &Template) -> Option<String> {
+        let context = self.tui.context();
+        if command.is_missing_items(&context) {
+            return None;
+        }
+        command.expand(&context).ok()
+    }
+
+    /// Suspends the TUI and runs a command in the terminal until it exits.
+    /// Returns whether the command succeeded.
+    async fn execute(&mut self, command: &Template) -> Result<bool> {
+        let Some(command) = self.expand(command) else {
+            return Ok(false);
+        };
+        self.stop_events().await;
+        let restored = self.restore_terminal();
+
+        let success = match (&restored, utils::foreground_command(&command)) {
+            (Ok(()), Ok(mut command)) => {
+                command.status().await.is_ok_and(|status| status.success())
+            }
+            _ => false,
+        };
+
+        // The terminal is taken back even if it was only partly restored
+        let resumed = self.resume_terminal();
+        self.start_events();
+        resumed?;
+        restored?;
+        Ok(success)
+    }
+
+    /// Restores the terminal and replaces tez with a command, which keeps
+    /// tez's stdout so it can be used in a pipeline in its place. Only returns
+    /// if the command couldn't be started, which isn't successful.
+    async fn become_command(&mut self, command: &Template) -> Result<bool> {
+        let Some(command) = self.expand(command) else {
+            return Ok(false);
+        };
+        let command = template::remove_temp_files_on_exit(&command);
+        self.stop_events().await;
+        let restored = self.restore_terminal();
+
+        if restored.is_ok() {
+            let error = match utils::foreground_command(&command) {
+                Ok(mut command) => match self.output.try_clone() {
+                    Ok(output) => command.stdout(output).as_std_mut().exec(),
+                    Err(error) => error,
+                },
+                Err(error) => error,
+            };
+            self.tui.show_error(format!("cannot become: {error}"));
+        }
+
+        let resumed = self.resume_terminal();
+        self.start_events();
+        resumed?;
+        restored?;
+        Ok(false)
+    }
+
+    /// Runs a command without suspending the TUI, discarding its output, and
+    /// waits until it exits like in fzf. Returns whether it succeeded.
+    async fn execute_silent(&mut self, command: &Template) -> bool {
+        let Some(command) = self.expand(command) else {
+            return false;
+        };
+        utils::shell_command(&command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .is_ok_and(|status| status.success())
     }
 
-    fn handle_key(&mut self, key: Key) -> Result<()> {
+    fn accept(&mut self) {
+        self.accepted = self.tui.accepted(self.config.mark_order());
+        if self.accepted.is_empty() {
+            self.state.exit(ExitStatus::NoMatch);
+        } else {
+            self.state.exit(ExitStatus::Accepted);
+        }
+    }
+
+    /// Accepts the only match or exits if nothing matched, once every item
+    /// has been read, and returns the exit status if the TUI isn't needed.
+    async fn auto_accept(
+        config: &Config,
+        tui: &mut Tui<'_>,
+        output: &mut File,
+    ) -> Result<Option<ExitStatus>> {
+        if !config.select_1() && !config.exit_0() {
+            return Ok(None);
+        }
+        tui.finish().await;
+        match tui.result_count() {
+            0 if config.exit_0() => Ok(Some(ExitStatus::NoMatch)),
+            1 if config.select_1() => {
+                write_items(output, tui.result(0), &config.output_separator())?;
+                Ok(Some(ExitStatus::Accepted))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn handle_key(&mut self, key: Key) -> Result<()> {
         let action = self
             .config
             .action(&key)
             .cloned()
             .unwrap_or(InputAction::Key(key).into());
-        self.handle_action(&action)?;
+        self.handle_action(&action).await?;
+        Ok(())
+    }
+
+    async fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<()> {
+        // Motion is reported as well, which doesn't change anything
+        if matches!(
+            mouse.kind,
+            MouseEventKind::Moved | MouseEventKind::Drag(_) | MouseEventKind::Up(_)
+        ) {
+            self.state.skip_frame();
+            return Ok(());
+        }
+        if let Some(action) = self.tui.handle_mouse(mouse) {
+            self.handle_action(&action).await?;
+        }
         Ok(())
     }
 }
+
+/// Writes items, each followed by the separator.
+fn write_items<W, I>(output: &mut W, items: I, separator: &str) -> Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = Item>,
+{
+    for item in items {
+        output.write_all(item.bytes())?;
+        output.write_all(separator.as_bytes())?;
+    }
+    output.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use ratatui::{layout::Rect, Viewport};
+
+    use super::*;
+
+    /// Creates an app with a config parsed from arguments, which isn't drawn.
+    fn app(args: &[&str]) -> App<'static> {
+        let config = Config::parse_from(args);
+        let (sender, _) = mpsc::unbounded_channel();
+        let (draw_sender, _) = watch::channel(());
+        let tui = Tui::new(&config, draw_sender).unwrap();
+        let terminal = Terminal::with_options(
+            CrosstermBackend::new(io::stdout()),
+            TerminalOptions {
+                viewport: Viewport::Fixed(Rect::default()),
+            },
+        )
+        .unwrap();
+        App {
+            config,
+            state: State::default(),
+            tui,
+            terminal,
+            sender,
+            events: task::spawn(async {}),
+            output: OpenOptions::new().write(true).open("/dev/null").unwrap(),
+            accepted: Vec::new(),
+        }
+    }
+
+    /// Handles a chain of actions, returning whether it succeeded.
+    async fn run(app: &mut App<'_>, chain: &str) -> bool {
+        app.handle_action(&chain.parse().unwrap()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn chain() {
+        let mut app = app(&["--command", "seq 3"]);
+        assert!(!run(&mut app, "execute-silent(false)+accept").await);
+        assert!(app.state.running());
+        assert!(run(&mut app, "execute-silent(true)+accept").await);
+        assert!(!app.state.running());
+
+        let mut app = self::app(&["--command", "seq 3"]);
+        assert!(run(&mut app, "exit+accept").await);
+        assert_eq!(app.state.exit_status(), ExitStatus::Aborted);
+    }
+
+    #[tokio::test]
+    async fn reload_stdin() {
+        let mut app = app(&[]);
+        assert!(!run(&mut app, "reload+accept").await);
+        assert!(app.state.running());
+    }
+
+    #[tokio::test]
+    async fn execute_silent() {
+        let path = env::temp_dir().join(format!("tez-test-{}", process::id()));
+        let command = format!("sleep 0.1; echo done > {}", path.display());
+        let mut app = app(&["--command", "seq 3"]);
+        let action = Action::Chain(vec![
+            Action::ExecuteSilent(Template::parse(&command)),
+            Action::Accept,
+        ]);
+        assert!(app.handle_action(&action).await.unwrap());
+        assert!(!app.state.running());
+        // The command has finished by the time tez would exit
+        assert_eq!(fs::read_to_string(&path).unwrap(), "done\n");
+        fs::remove_file(path).unwrap();
+
+        let action = Action::ExecuteSilent(Template::parse("exit 1"));
+        assert!(!app.handle_action(&action).await.unwrap());
+    }
+}
@@ -8,7 +8,7 @@ use derive_more::From;
 use serde::Deserialize;
 
 use crate::types::{
-    action::{Action, InputAction, TuiAction},
+    action::{Action, InputAction, PreviewAction, TuiAction},
     bind::Bind,
     key::Key,
 };
@@ -27,10 +27,14 @@ impl Default for Binds {
     fn default() -> Self {
         let map = HashMap::from([
             (Key::new(K::Char('c'), M::CONTROL), Action::Exit),
+            (Key::new(K::Enter, M::NONE), Action::Accept),
             (Key::new(K::Char('n'), M::CONTROL), TuiAction::Next.into()),
             (Key::new(K::Char('p'), M::CONTROL), TuiAction::Previous.into()),
             (Key::new(K::Char('a'), M::ALT), TuiAction::First.into()),
             (Key::new(K::Char('e'), M::ALT), TuiAction::Last.into()),
+            (Key::new(K::Tab, M::NONE), TuiAction::ToggleMark.into()),
+            (Key::new(K::Up, M::SHIFT), PreviewAction::ScrollUp.into()),
+            (Key::new(K::Down, M::SHIFT), PreviewAction::ScrollDown.into()),
 
             (Key::new(K::Left, M::NONE), InputAction::MoveBack.into()),
             (Key::new(K::Down, M::NONE), InputAction::MoveDown.into()),
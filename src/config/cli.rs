@@ -28,7 +28,7 @@ pub struct Cli {
     binds: Vec<Bind>,
 
     #[command(subcommand)]
-    command: Command,
+    command: Option<Command>,
 }
 
 #[derive(Clone, Debug, Subcommand)]
@@ -65,7 +65,8 @@ impl Cli {
 
     pub fn active_script(&self) -> Option<&str> {
         match &self.command {
-            Command::Run { script } => Some(script),
+            Some(Command::Run { script }) => Some(script),
+            None => None,
         }
     }
 }
@@ -95,3 +96,15 @@ impl Display for ConfigDir {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn verify() {
+        Cli::command().debug_assert();
+    }
+}
@@ -15,11 +15,13 @@ mod cli;
 mod full;
 mod partial;
 mod scripts;
+mod theme;
 
 #[derive(Clone, Debug, Default)]
 pub struct Config {
     default_binds: Binds,
     main_config: FullConfig,
+    #[allow(dead_code)]
     script_configs: Scripts,
     active_script_config: Option<Rc<ScriptConfig>>,
     cli_config: FullConfig,
@@ -28,9 +30,20 @@ pub struct Config {
 
 impl Config {
     pub fn load() -> Result<Self> {
-        let cli = Cli::parse();
+        Config::from_cli(Cli::parse())
+    }
+
+    /// Loads the config from arguments without config files.
+    #[cfg(test)]
+    pub fn parse_from(args: &[&str]) -> Self {
+        let args = ["tez", "--disable-config"].iter().chain(args);
+        Config::from_cli(Cli::parse_from(args)).expect("config should be valid")
+    }
+
+    fn from_cli(cli: Cli) -> Result<Self> {
         let main_config = cli
             .config_file()
+            .filter(|path| path.exists())
             .map(FullConfig::parse)
             .unwrap_or(Ok(FullConfig::default()))?;
         let script_configs = cli
@@ -3,7 +3,27 @@ use clap::Args;
 use ratatui::{layout::Rect, Viewport};
 use serde::Deserialize;
 
-use crate::types::{alignment::Alignment, extent::Extent};
+use crate::{
+    item::ItemFormat,
+    searcher::SearcherSource,
+    template::Template,
+    types::{
+        alignment::Alignment,
+        column::Column,
+        extent::Extent,
+        fields::Fields,
+        list_layout::ListLayout,
+        mark_order::MarkOrder,
+        matching::{Algorithm, CaseMode, MatchOptions, NormalizationMode},
+        preview_position::PreviewPosition,
+        sorting::{SortOptions, Tiebreak},
+        status_position::StatusPosition,
+        theme::Palette,
+    },
+    utils,
+};
+
+use super::theme::Theme;
 
 #[derive(Args, Clone, Debug, Default, Deserialize, PartialEq)]
 pub struct PartialConfig {
@@ -12,6 +32,99 @@ pub struct PartialConfig {
     #[serde(default)]
     pub disable_default_binds: bool,
 
+    /// Read items from a command instead of stdin
+    #[arg(long, value_name = "COMMAND")]
+    command: Option<String>,
+    /// Set initial query
+    #[arg(short, long, value_name = "QUERY")]
+    query: Option<String>,
+    /// Accept the only match without starting the TUI
+    #[arg(short = '1', long, num_args = 0, default_missing_value = "true")]
+    select_1: Option<bool>,
+    /// Exit without starting the TUI if nothing matches
+    #[arg(short = '0', long, num_args = 0, default_missing_value = "true")]
+    exit_0: Option<bool>,
+    /// Print items that match a query without starting the TUI
+    #[arg(short, long, value_name = "QUERY")]
+    filter: Option<String>,
+    /// Read items separated by NUL instead of newlines
+    #[arg(long, num_args = 0, default_missing_value = "true")]
+    read0: Option<bool>,
+    /// Set string that separates items that are read (supports \0, \t and \n)
+    #[arg(long, value_name = "STRING")]
+    separator: Option<String>,
+    /// Print items separated by NUL instead of newlines
+    #[arg(long, num_args = 0, default_missing_value = "true")]
+    print0: Option<bool>,
+    /// Set string that separates items that are printed
+    #[arg(long, value_name = "STRING")]
+    output_separator: Option<String>,
+    /// Show ANSI colors of items and remove them from the searched text
+    #[arg(long, num_args = 0, default_missing_value = "true")]
+    ansi: Option<bool>,
+    /// Keep ANSI escapes in items that are printed
+    #[arg(long, num_args = 0, default_missing_value = "true")]
+    keep_ansi: Option<bool>,
+    /// Set field delimiter (defaults to whitespace)
+    #[arg(long, value_name = "STRING")]
+    delimiter: Option<String>,
+    /// Set fields that are searched
+    #[arg(long, value_name = "FIELDS")]
+    nth: Option<Fields>,
+    /// Set fields that are displayed
+    #[arg(long, value_name = "FIELDS")]
+    with_nth: Option<Fields>,
+    /// Add a column that's searched with `NAME:QUERY`
+    #[arg(long = "column", value_name = "NAME=FIELDS")]
+    #[serde(default, deserialize_with = "Column::deserialize_table")]
+    columns: Vec<Column>,
+    /// Set case sensitivity
+    #[arg(long, value_name = "MODE")]
+    case: Option<CaseMode>,
+    /// Set whether diacritics are ignored
+    #[arg(long, value_name = "MODE")]
+    normalization: Option<NormalizationMode>,
+    /// Set how query words are matched
+    #[arg(long)]
+    algorithm: Option<Algorithm>,
+    /// Keep items in input order instead of sorting them by score
+    #[arg(long, num_args = 0, default_missing_value = "true")]
+    no_sort: Option<bool>,
+    /// Set how results with the same score are ordered [default: length,index]
+    #[arg(long, value_name = "CRITERIA", value_delimiter = ',')]
+    tiebreak: Option<Vec<Tiebreak>>,
+
+    /// Set the order marked items are accepted in
+    #[arg(long, value_name = "ORDER")]
+    mark_order: Option<MarkOrder>,
+
+    /// Set layout of the list and input
+    #[arg(long)]
+    layout: Option<ListLayout>,
+    /// Hide borders of the list and input
+    #[arg(long, num_args = 0, default_missing_value = "true")]
+    no_border: Option<bool>,
+    /// Set status line position
+    #[arg(long, value_name = "POSITION")]
+    status_position: Option<StatusPosition>,
+    /// Disable mouse support
+    #[arg(long, num_args = 0, default_missing_value = "true")]
+    no_mouse: Option<bool>,
+
+    #[command(flatten)]
+    #[serde(default)]
+    theme: Theme,
+
+    /// Set command that previews the selected item
+    #[arg(long, value_name = "COMMAND")]
+    preview: Option<String>,
+    /// Set preview position
+    #[arg(long, value_name = "POSITION")]
+    preview_position: Option<PreviewPosition>,
+    /// Set preview size
+    #[arg(long, value_name = "EXTENT")]
+    preview_size: Option<Extent>,
+
     /// Set viewport width
     #[arg(short = 'W', long, value_name = "EXTENT")]
     width: Option<Extent>,
@@ -27,12 +140,153 @@ impl PartialConfig {
     pub fn overwrite(&self, other: &Self) -> Self {
         PartialConfig {
             disable_default_binds: other.disable_default_binds,
+            command: other.command.clone().or_else(|| self.command.clone()),
+            query: other.query.clone().or_else(|| self.query.clone()),
+            select_1: other.select_1.or(self.select_1),
+            exit_0: other.exit_0.or(self.exit_0),
+            filter: other.filter.clone().or_else(|| self.filter.clone()),
+            read0: other.read0.or(self.read0),
+            separator: other.separator.clone().or_else(|| self.separator.clone()),
+            print0: other.print0.or(self.print0),
+            output_separator: other
+                .output_separator
+                .clone()
+                .or_else(|| self.output_separator.clone()),
+            ansi: other.ansi.or(self.ansi),
+            keep_ansi: other.keep_ansi.or(self.keep_ansi),
+            delimiter: other.delimiter.clone().or_else(|| self.delimiter.clone()),
+            nth: other.nth.clone().or_else(|| self.nth.clone()),
+            with_nth: other.with_nth.clone().or_else(|| self.with_nth.clone()),
+            columns: if other.columns.is_empty() {
+                self.columns.clone()
+            } else {
+                other.columns.clone()
+            },
+            case: other.case.or(self.case),
+            normalization: other.normalization.or(self.normalization),
+            algorithm: other.algorithm.or(self.algorithm),
+            no_sort: other.no_sort.or(self.no_sort),
+            tiebreak: other.tiebreak.clone().or_else(|| self.tiebreak.clone()),
+            mark_order: other.mark_order.or(self.mark_order),
+            layout: other.layout.or(self.layout),
+            no_border: other.no_border.or(self.no_border),
+            status_position: other.status_position.or(self.status_position),
+            no_mouse: other.no_mouse.or(self.no_mouse),
+            theme: self.theme.overwrite(&other.theme),
+            preview: other.preview.clone().or_else(|| self.preview.clone()),
+            preview_position: other.preview_position.or(self.preview_position),
+            preview_size: other.preview_size.or(self.preview_size),
             width: other.width.or(self.width),
             height: other.height.or(self.height),
             alignment: other.alignment.or(self.alignment),
         }
     }
 
+    pub fn source(&self) -> SearcherSource {
+        match &self.command {
+            Some(command) => SearcherSource::Command(Template::parse(command)),
+            None => SearcherSource::Stdin,
+        }
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    pub fn select_1(&self) -> bool {
+        self.select_1.unwrap_or(false)
+    }
+
+    pub fn exit_0(&self) -> bool {
+        self.exit_0.unwrap_or(false)
+    }
+
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_deref()
+    }
+
+    pub fn item_format(&self) -> ItemFormat {
+        ItemFormat::new(
+            self.delimiter.clone(),
+            self.nth.clone(),
+            self.with_nth.clone(),
+            self.columns.clone(),
+        )
+        .with_separator(match self.read0 {
+            Some(true) => "\0".to_string(),
+            _ => self
+                .separator
+                .as_deref()
+                .map(utils::unescape)
+                .unwrap_or_default(),
+        })
+        .with_ansi(self.ansi.unwrap_or(false), self.keep_ansi.unwrap_or(false))
+    }
+
+    pub fn output_separator(&self) -> String {
+        match self.print0 {
+            Some(true) => "\0".to_string(),
+            _ => self
+                .output_separator
+                .as_deref()
+                .map_or("\n".to_string(), utils::unescape),
+        }
+    }
+
+    pub fn match_options(&self) -> MatchOptions {
+        MatchOptions {
+            case: self.case.unwrap_or_default(),
+            normalization: self.normalization.unwrap_or_default(),
+            algorithm: self.algorithm.unwrap_or_default(),
+        }
+    }
+
+    pub fn sort_options(&self) -> SortOptions {
+        SortOptions {
+            sort: !self.no_sort.unwrap_or(false),
+            tiebreaks: self
+                .tiebreak
+                .clone()
+                .unwrap_or_else(|| SortOptions::DEFAULT_TIEBREAKS.to_vec()),
+        }
+    }
+
+    pub fn mark_order(&self) -> MarkOrder {
+        self.mark_order.unwrap_or_default()
+    }
+
+    pub fn layout(&self) -> ListLayout {
+        self.layout.unwrap_or_default()
+    }
+
+    pub fn borders(&self) -> bool {
+        !self.no_border.unwrap_or(false)
+    }
+
+    pub fn status_position(&self) -> StatusPosition {
+        self.status_position.unwrap_or_default()
+    }
+
+    pub fn mouse(&self) -> bool {
+        !self.no_mouse.unwrap_or(false)
+    }
+
+    pub fn palette(&self) -> Palette {
+        self.theme.palette()
+    }
+
+    pub fn preview(&self) -> Option<&str> {
+        self.preview.as_deref()
+    }
+
+    pub fn preview_position(&self) -> PreviewPosition {
+        self.preview_position.unwrap_or_default()
+    }
+
+    pub fn preview_size(&self) -> Extent {
+        self.preview_size.unwrap_or(Extent::Percentage(0.5))
+    }
+
     pub fn is_inline(&self) -> bool {
         self.height.is_some()
     }
@@ -82,6 +336,35 @@ mod tests {
         let a = PartialConfig::default();
         let b = PartialConfig {
             disable_default_binds: true,
+            command: Some(String::new()),
+            query: Some(String::new()),
+            select_1: Some(true),
+            exit_0: Some(true),
+            filter: Some(String::new()),
+            read0: Some(true),
+            separator: Some(String::new()),
+            print0: Some(true),
+            output_separator: Some(String::new()),
+            ansi: Some(true),
+            keep_ansi: Some(true),
+            delimiter: Some(String::new()),
+            nth: "1".parse().ok(),
+            with_nth: "1".parse().ok(),
+            columns: vec!["name=1".parse().unwrap()],
+            case: Some(CaseMode::default()),
+            normalization: Some(NormalizationMode::default()),
+            algorithm: Some(Algorithm::default()),
+            no_sort: Some(true),
+            tiebreak: Some(vec![Tiebreak::Begin]),
+            mark_order: Some(MarkOrder::default()),
+            layout: Some(ListLayout::Reverse),
+            no_border: Some(true),
+            status_position: Some(StatusPosition::Line),
+            no_mouse: Some(true),
+            theme: toml::from_str("preset = 'light'").unwrap(),
+            preview: Some(String::new()),
+            preview_position: Some(PreviewPosition::default()),
+            preview_size: Some(Extent::ZERO),
             height: Some(Extent::ZERO),
             width: Some(Extent::ZERO),
             alignment: Some(Alignment::default()),
@@ -50,6 +50,7 @@ pub struct ScriptConfig {
     #[serde(flatten)]
     config: FullConfig,
     name: Rc<str>,
+    #[allow(dead_code)]
     #[serde(default)]
     adjacent_scripts: Vec<String>,
 }
@@ -0,0 +1,88 @@
+use clap::Args;
+use serde::Deserialize;
+
+use crate::types::{
+    style::Style,
+    theme::{Palette, ThemePreset},
+};
+
+// Styles of the TUI, set in the `[theme]` table. Styles that aren't set are
+// taken from the preset. Not a doc comment, since clap would use it as the
+// description of tez.
+#[derive(Args, Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Theme {
+    /// Set the theme that unset styles are taken from
+    #[arg(long = "theme", value_name = "PRESET")]
+    preset: Option<ThemePreset>,
+    /// Set the style of the selected item
+    #[arg(id = "selected_style", long = "selected-style", value_name = "STYLE")]
+    selected: Option<Style>,
+    /// Set the style of matched characters
+    #[arg(id = "match_style", long = "match-style", value_name = "STYLE")]
+    matched: Option<Style>,
+    /// Set the style of the marker of marked items
+    #[arg(id = "marker_style", long = "marker-style", value_name = "STYLE")]
+    marker: Option<Style>,
+    /// Set the style of borders
+    #[arg(id = "border_style", long = "border-style", value_name = "STYLE")]
+    border: Option<Style>,
+    /// Set the style of the query
+    #[arg(id = "prompt_style", long = "prompt-style", value_name = "STYLE")]
+    prompt: Option<Style>,
+    /// Set the style of the status line
+    #[arg(id = "status_style", long = "status-style", value_name = "STYLE")]
+    status: Option<Style>,
+    /// Set the style of the preview
+    #[arg(id = "preview_style", long = "preview-style", value_name = "STYLE")]
+    preview: Option<Style>,
+}
+
+impl Theme {
+    pub fn overwrite(&self, other: &Self) -> Self {
+        Theme {
+            preset: other.preset.or(self.preset),
+            selected: other.selected.or(self.selected),
+            matched: other.matched.or(self.matched),
+            marker: other.marker.or(self.marker),
+            border: other.border.or(self.border),
+            prompt: other.prompt.or(self.prompt),
+            status: other.status.or(self.status),
+            preview: other.preview.or(self.preview),
+        }
+    }
+
+    pub fn palette(&self) -> Palette {
+        let preset = self.preset.unwrap_or_default().palette();
+        let style = |style: Option<Style>, default| style.map_or(default, Into::into);
+        Palette {
+            selected: style(self.selected, preset.selected),
+            matched: style(self.matched, preset.matched),
+            marker: style(self.marker, preset.marker),
+            border: style(self.border, preset.border),
+            prompt: style(self.prompt, preset.prompt),
+            status: style(self.status, preset.status),
+            preview: style(self.preview, preset.preview),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::{self, Color};
+
+    use super::*;
+
+    #[test]
+    fn palette() {
+        let theme: Theme = toml::from_str(
+            r#"
+            preset = "light"
+            matched = "red"
+            "#,
+        )
+        .unwrap();
+        let palette = theme.palette();
+        assert_eq!(palette.matched, style::Style::new().fg(Color::Red));
+        assert_eq!(palette.marker, ThemePreset::Light.palette().marker);
+    }
+}
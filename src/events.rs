@@ -2,7 +2,7 @@ use derive_more::From;
 use futures::StreamExt;
 use tokio::sync::mpsc::UnboundedSender;
 
-use crossterm::event::{Event, EventStream};
+use crossterm::event::{Event, EventStream, MouseEvent};
 
 use crate::types::{action::Action, key::Key};
 
@@ -11,6 +11,10 @@ pub enum Message {
     Error(anyhow::Error),
     Action(Action),
     Key(Key),
+    Mouse(MouseEvent),
+    Paste(String),
+    /// New width and height of the terminal
+    Resize(u16, u16),
 }
 
 pub async fn handle_events(sender: UnboundedSender<Message>) {
@@ -18,6 +22,9 @@ pub async fn handle_events(sender: UnboundedSender<Message>) {
         if let Some(message) = match event {
             Err(error) => Some(Message::Error(error.into())),
             Ok(Event::Key(key)) => Some(Message::Key(key.into())),
+            Ok(Event::Mouse(mouse)) => Some(Message::Mouse(mouse)),
+            Ok(Event::Paste(text)) => Some(Message::Paste(text)),
+            Ok(Event::Resize(width, height)) => Some(Message::Resize(width, height)),
             _ => None,
         } {
             let _ = sender.send(message);
@@ -0,0 +1,336 @@
+use std::{borrow::Cow, ops::Range};
+
+use ratatui::style::Style;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    ansi::{self, Styles},
+    types::{
+        column::Column,
+        fields::{self, Fields},
+    },
+    utils,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Item {
+    /// Index of the item in the order it was injected
+    pub index: u32,
+    /// Text the item was created from, which is output when it's accepted
+    pub text: String,
+    // Original bytes of the text if they weren't valid UTF-8
+    bytes: Option<Vec<u8>>,
+    // Text that's displayed instead of the original text
+    display: Option<String>,
+    // Styles of byte ranges of the displayed text, parsed from ANSI escapes
+    styles: Option<Styles>,
+    // Byte ranges of the displayed text that are searched
+    search_ranges: Option<Vec<Range<usize>>>,
+}
+
+impl Item {
+    /// Keeps the bytes the text was lossily converted from, so they can be
+    /// output unchanged.
+    pub fn with_bytes(mut self, bytes: Vec<u8>) -> Self {
+        self.bytes = Some(bytes);
+        self
+    }
+
+    /// Returns the bytes that are output when the item is accepted.
+    pub fn bytes(&self) -> &[u8] {
+        self.bytes.as_deref().unwrap_or(self.text.as_bytes())
+    }
+
+    pub fn display(&self) -> &str {
+        self.display.as_deref().unwrap_or(&self.text)
+    }
+
+    pub fn styles(&self) -> &[(Range<usize>, Style)] {
+        self.styles.as_deref().unwrap_or_default()
+    }
+
+    pub fn search_text(&self) -> Cow<'_, str> {
+        match &self.search_ranges {
+            Some(ranges) => ranges
+                .iter()
+                .map(|range| &self.display()[range.clone()])
+                .collect(),
+            None => self.display().into(),
+        }
+    }
+
+    /// Converts indices of graphemes in the searched text to indices of
+    /// graphemes in the displayed text.
+    pub fn display_indices(&self, indices: Vec<u32>) -> Vec<u32> {
+        match &self.search_ranges {
+            Some(ranges) => map_indices(self.display(), ranges, indices),
+            None => indices,
+        }
+    }
+}
+
+/// Converts indices of graphemes in the concatenation of byte ranges of a text
+/// to indices of graphemes in the whole text.
+fn map_indices(text: &str, ranges: &[Range<usize>], indices: Vec<u32>) -> Vec<u32> {
+    let ranges: Vec<_> = ranges
+        .iter()
+        .map(|range| {
+            let start = text[..range.start].graphemes(true).count();
+            start..start + text[range.clone()].graphemes(true).count()
+        })
+        .collect();
+    indices
+        .into_iter()
+        .filter_map(|mut i| {
+            for range in &ranges {
+                if (i as usize) < range.len() {
+                    return Some(range.start as u32 + i);
+                }
+                i -= range.len() as u32;
+            }
+            None
+        })
+        .collect()
+}
+
+/// Describes how input is split into items and how they're searched.
+#[derive(Clone, Debug, Default)]
+pub struct ItemFormat {
+    /// String that separates items, or newlines if empty
+    separator: String,
+    /// Whether ANSI escapes are parsed
+    ansi: bool,
+    /// Whether ANSI escapes are kept in the text that's output
+    keep_ansi: bool,
+    delimiter: Option<String>,
+    /// Fields that are searched
+    nth: Option<Fields>,
+    /// Fields that are displayed
+    with_nth: Option<Fields>,
+    /// Fields that can be searched separately, after the searched fields
+    columns: Vec<Column>,
+}
+
+impl ItemFormat {
+    pub fn new(
+        delimiter: Option<String>,
+        nth: Option<Fields>,
+        with_nth: Option<Fields>,
+        columns: Vec<Column>,
+    ) -> Self {
+        ItemFormat {
+            separator: String::new(),
+            ansi: false,
+            keep_ansi: false,
+            delimiter,
+            nth,
+            with_nth,
+            columns,
+        }
+    }
+
+    pub fn with_separator(mut self, separator: String) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn with_ansi(mut self, ansi: bool, keep_ansi: bool) -> Self {
+        self.ansi = ansi;
+        self.keep_ansi = keep_ansi;
+        self
+    }
+
+    pub fn delimiter(&self) -> Option<&str> {
+        self.delimiter.as_deref()
+    }
+
+    /// Checks if the text of items is output as it was read.
+    pub fn keeps_text(&self) -> bool {
+        !self.ansi || self.keep_ansi
+    }
+
+    /// Returns the bytes that separate items.
+    pub fn separator(&self) -> &[u8] {
+        if self.separator.is_empty() {
+            b"\n"
+        } else {
+            self.separator.as_bytes()
+        }
+    }
+
+    /// Returns the number of searcher columns needed for the format.
+    pub fn column_count(&self) -> u32 {
+        1 + self.columns.len() as u32
+    }
+
+    /// Returns the text of an item's searcher column.
+    pub fn column_text<'a>(&self, item: &'a Item, column: usize) -> Cow<'a, str> {
+        match column.checked_sub(1) {
+            None => item.search_text(),
+            Some(i) => self.columns[i]
+                .fields
+                .select(item.display(), self.delimiter.as_deref())
+                .into(),
+        }
+    }
+
+    /// Converts indices of graphemes in an item's searcher column to indices
+    /// of graphemes in its displayed text.
+    pub fn display_indices(&self, item: &Item, column: usize, indices: Vec<u32>) -> Vec<u32> {
+        let Some(i) = column.checked_sub(1) else {
+            return item.display_indices(indices);
+        };
+        let display = item.display();
+        let fields = fields::split(display, self.delimiter.as_deref());
+        let ranges: Vec<_> = self.columns[i]
+            .fields
+            .indices(fields.len())
+            .map(|i| fields[i].clone())
+            .collect();
+        map_indices(display, &ranges, indices)
+    }
+
+    /// Splits a query into the patterns of every searcher column. Words
+    /// starting with `name:` are searched in the column called `name`.
+    pub fn split_query(&self, query: &str) -> Vec<String> {
+        let mut patterns = vec![String::new(); self.column_count() as usize];
+        for word in utils::split_words(query) {
+            let (column, word) = self.column_word(word);
+            if word.is_empty() {
+                continue;
+            }
+            if !patterns[column].is_empty() {
+                patterns[column].push(' ');
+            }
+            patterns[column].push_str(&word);
+        }
+        patterns
+    }
+
+    // Finds the column a word belongs to and removes its name from the word,
+    // while keeping any operators in front of it
+    fn column_word<'a>(&self, word: &'a str) -> (usize, Cow<'a, str>) {
+        let name_start = word.len() - word.trim_start_matches(['!', '^', '\'']).len();
+        let (operators, rest) = word.split_at(name_start);
+        self.columns
+            .iter()
+            .enumerate()
+            .find_map(|(i, column)| {
+                let atom = rest.strip_prefix(&column.name)?.strip_prefix(':')?;
+                Some((
+                    i + 1,
+                    if atom.is_empty() {
+                        "".into()
+                    } else {
+                        format!("{operators}{atom}").into()
+                    },
+                ))
+            })
+            .unwrap_or((0, word.into()))
+    }
+
+    pub fn item(&self, index: u32, text: String) -> Item {
+        let delimiter = self.delimiter.as_deref();
+        let display = self
+            .with_nth
+            .as_ref()
+            .map(|fields| fields.select(&text, delimiter));
+        let (text, display, styles) = if self.ansi {
+            self.strip_ansi(text, display)
+        } else {
+            (text, display, None)
+        };
+        let search_ranges = self.nth.as_ref().map(|nth| {
+            let fields = fields::split(display.as_deref().unwrap_or(&text), delimiter);
+            nth.indices(fields.len())
+                .map(|i| fields[i].clone())
+                .collect()
+        });
+        Item {
+            index,
+            text,
+            bytes: None,
+            display,
+            styles,
+            search_ranges,
+        }
+    }
+
+    // Removes ANSI escapes from the displayed text and parses their styles. They
+    // are also removed from the text, unless they should be output.
+    fn strip_ansi(
+        &self,
+        text: String,
+        display: Option<String>,
+    ) -> (String, Option<String>, Option<Styles>) {
+        let (stripped, styles) = ansi::strip(display.as_deref().unwrap_or(&text));
+        let styles = (!styles.is_empty()).then_some(styles);
+        match display {
+            Some(_) if self.keep_ansi => (text, Some(stripped), styles),
+            Some(_) => (ansi::strip(&text).0, Some(stripped), styles),
+            None if stripped.len() == text.len() => (text, None, styles),
+            None if self.keep_ansi => (text, Some(stripped), styles),
+            None => (stripped, None, styles),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Color;
+
+    use super::*;
+
+    #[test]
+    fn item() {
+        let format = ItemFormat::new(None, "2".parse().ok(), "2..".parse().ok(), Vec::new());
+        let item = format.item(0, "a bc dé f".to_string());
+        assert_eq!(item.display(), "bc dé f");
+        assert_eq!(item.search_text(), "dé ");
+        assert_eq!(item.display_indices(vec![0, 1, 2]), [3, 4, 5]);
+
+        let format = ItemFormat::new(Some(":".to_string()), "-1,1".parse().ok(), None, Vec::new());
+        let item = format.item(0, "a:b:c".to_string());
+        assert_eq!(item.display(), "a:b:c");
+        assert_eq!(item.search_text(), "ca:");
+        assert_eq!(item.display_indices(vec![0, 1, 3]), [4, 0]);
+    }
+
+    #[test]
+    fn ansi() {
+        let red = Style::new().fg(Color::Indexed(1));
+        let format = ItemFormat::default().with_ansi(true, false);
+        let item = format.item(0, "\x1b[31ma\x1b[m b".to_string());
+        assert_eq!(item.text, "a b");
+        assert_eq!(item.display(), "a b");
+        assert_eq!(item.styles(), [(0..1, red)]);
+
+        let format =
+            ItemFormat::new(None, None, "2".parse().ok(), Vec::new()).with_ansi(true, true);
+        let item = format.item(0, "a \x1b[31mb\x1b[m".to_string());
+        assert_eq!(item.text, "a \x1b[31mb\x1b[m");
+        assert_eq!(item.display(), "b");
+        assert_eq!(item.search_text(), "b");
+        assert_eq!(item.styles(), [(0..1, red)]);
+    }
+
+    #[test]
+    fn split_query() {
+        let format = ItemFormat::new(
+            None,
+            None,
+            None,
+            vec!["name=1".parse().unwrap(), "ext=2".parse().unwrap()],
+        );
+        assert_eq!(format.split_query(""), ["", "", ""]);
+        assert_eq!(
+            format.split_query("foo name:bar  !ext:rs a\\ b"),
+            ["foo a\\ b", "bar", "!rs"]
+        );
+        assert_eq!(format.split_query("name: other:x"), ["other:x", "", ""]);
+
+        let item = format.item(0, "a b".to_string());
+        assert_eq!(format.column_text(&item, 0), "a b");
+        assert_eq!(format.column_text(&item, 2), "b");
+    }
+}
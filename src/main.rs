@@ -1,18 +1,27 @@
+use std::process::ExitCode;
+
 use app::App;
 use crossterm::style::Stylize;
 
+mod ansi;
 mod app;
 mod config;
 mod events;
+mod item;
 mod searcher;
 mod state;
+mod template;
 mod tui;
 mod types;
 mod utils;
 
 #[tokio::main]
-async fn main() {
-    if let Err(error) = App::run().await {
-        eprintln!("{} {error}", "error:".red());
-    };
+async fn main() -> ExitCode {
+    match App::run().await {
+        Ok(exit_code) => exit_code,
+        Err(error) => {
+            eprintln!("{} {error}", "error:".red());
+            ExitCode::from(2)
+        }
+    }
 }
@@ -1,120 +1,736 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    fmt::{self, Display, Formatter},
+    mem,
+    process::{ExitStatus, Stdio},
+    sync::Arc,
+    time::Duration,
+};
 
 use nucleo::{
-    pattern::{CaseMatching, Normalization},
-    Injector, Nucleo,
+    pattern::{Atom, MultiPattern},
+    Injector, Matcher, Nucleo, Snapshot,
 };
 use tokio::{
-    io::{self, AsyncBufReadExt, BufReader},
+    io::{self, AsyncBufReadExt, AsyncRead, BufReader},
+    join,
     sync::{
         mpsc::UnboundedSender,
-        watch::{Receiver, Sender},
+        watch::{self, Receiver, Sender},
     },
-    task,
+    task::{self, JoinHandle},
     time::sleep,
 };
 
-use crate::{events::Message, types::action::Action};
+use crate::{
+    events::Message,
+    item::{Item, ItemFormat},
+    template::{Context, Template},
+    types::{
+        action::Action,
+        matching::MatchOptions,
+        sorting::{SortOptions, Tiebreak},
+    },
+    utils::{self, ProcessGroup},
+};
 
 pub enum SearcherSource {
     Stdin,
-    Command(String),
+    Command(Template),
 }
 
 impl SearcherSource {
-    pub fn inject(&self, injector: Injector<String>) {
-        task::spawn(match self {
-            SearcherSource::Stdin => SearcherSource::inject_stdin(injector),
-            SearcherSource::Command(_) => todo!(),
-        });
-    }
-
-    async fn inject_stdin(injector: Injector<String>) {
-        let mut lines = BufReader::new(io::stdin()).lines();
-        while let Ok(Some(line)) = lines.next_line().await {
-            injector.push(line, |line, columns| {
-                columns[0] = line.as_str().into();
+    pub fn inject(
+        &self,
+        context: &Context,
+        injector: Injector<Item>,
+        format: Arc<ItemFormat>,
+        status: Sender<SourceStatus>,
+        draw_sender: Sender<()>,
+    ) -> JoinHandle<()> {
+        match self {
+            SearcherSource::Stdin => task::spawn(SearcherSource::inject_stdin(
+                injector,
+                format,
+                status,
+                draw_sender,
+            )),
+            SearcherSource::Command(command) => task::spawn(SearcherSource::inject_command(
+                command.expand(context),
+                injector,
+                format,
+                status,
+                draw_sender,
+            )),
+        }
+    }
+
+    async fn inject_stdin(
+        injector: Injector<Item>,
+        format: Arc<ItemFormat>,
+        status: Sender<SourceStatus>,
+        draw_sender: Sender<()>,
+    ) {
+        SearcherSource::inject_lines(io::stdin(), &injector, &format, &status).await;
+        // Draw once more, so it's noticed that the searcher is finished
+        let _ = draw_sender.send(());
+    }
+
+    async fn inject_command(
+        command: io::Result<String>,
+        injector: Injector<Item>,
+        format: Arc<ItemFormat>,
+        status: Sender<SourceStatus>,
+        draw_sender: Sender<()>,
+    ) {
+        let mut child = match command.and_then(|command| {
+            utils::shell_command(&command)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+        }) {
+            Ok(child) => child,
+            Err(error) => {
+                status.send_modify(|status| status.stderr = Some(error.to_string()));
+                let _ = draw_sender.send(());
+                return;
+            }
+        };
+        let mut process_group = ProcessGroup::new(child.id());
+
+        let stdout = child.stdout.take().expect("stdout should be piped");
+        let stderr = child.stderr.take().expect("stderr should be piped");
+        let report_stderr = async {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                status.send_modify(|status| status.stderr = Some(line));
+                let _ = draw_sender.send(());
+            }
+        };
+        join!(
+            SearcherSource::inject_lines(stdout, &injector, &format, &status),
+            report_stderr
+        );
+
+        let exit_status = child.wait().await;
+        process_group.release();
+        status.send_modify(|status| status.exit_status = exit_status.ok());
+        let _ = draw_sender.send(());
+    }
+
+    async fn inject_lines<R>(
+        reader: R,
+        injector: &Injector<Item>,
+        format: &ItemFormat,
+        status: &Sender<SourceStatus>,
+    ) where
+        R: AsyncRead + Unpin,
+    {
+        let separator = format.separator();
+        let last_byte = separator[separator.len() - 1];
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        let mut index = 0;
+        while let Ok(read) = reader.read_until(last_byte, &mut buf).await {
+            if buf.ends_with(separator) {
+                buf.truncate(buf.len() - separator.len());
+                // Lines can also end with \r\n, like with `AsyncBufReadExt::lines`
+                if separator == b"\n" && buf.ends_with(b"\r") {
+                    buf.pop();
+                }
+            } else if read != 0 {
+                // The last byte of the separator was read without the rest
+                continue;
+            } else if buf.is_empty() {
+                break;
+            }
+
+            let item = match String::from_utf8(mem::take(&mut buf)) {
+                Ok(text) => format.item(index, text),
+                Err(error) => {
+                    status.send_modify(|status| status.invalid_lines += 1);
+                    let bytes = error.into_bytes();
+                    let text = String::from_utf8_lossy(&bytes).into_owned();
+                    let item = format.item(index, text);
+                    // Bytes can't be output if ANSI escapes were removed from the text
+                    if format.keeps_text() {
+                        item.with_bytes(bytes)
+                    } else {
+                        item
+                    }
+                }
+            };
+            injector.push(item, |item, columns| {
+                for (i, column) in columns.iter_mut().enumerate() {
+                    *column = format.column_text(item, i).as_ref().into();
+                }
             });
+            index += 1;
         }
     }
 }
 
+pub struct SearchResult {
+    pub item: Item,
+    /// Sorted indices of the displayed graphemes that matched the pattern
+    pub indices: Vec<u32>,
+}
+
+/// Information about a source that isn't part of the injected items.
+#[derive(Clone, Debug, Default)]
+pub struct SourceStatus {
+    stderr: Option<String>,
+    exit_status: Option<ExitStatus>,
+    /// Number of items that weren't valid UTF-8
+    invalid_lines: u32,
+}
+
+impl SourceStatus {
+    pub fn is_empty(&self) -> bool {
+        self.stderr.is_none()
+            && self.exit_status.is_none_or(|status| status.success())
+            && self.invalid_lines == 0
+    }
+}
+
+impl Display for SourceStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (
+            &self.stderr,
+            self.exit_status.filter(|status| !status.success()),
+        ) {
+            (Some(stderr), Some(status)) => write!(f, "{stderr} ({status})"),
+            (Some(stderr), None) => stderr.fmt(f),
+            (None, Some(status)) => status.fmt(f),
+            (None, None) => Ok(()),
+        }?;
+        if self.invalid_lines > 0 {
+            if self.stderr.is_some() || self.exit_status.is_some_and(|status| !status.success()) {
+                " | ".fmt(f)?;
+            }
+            write!(f, "invalid UTF-8: {}", self.invalid_lines)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sort keys of matched items, kept between ticks so only newly matched items
+/// are scored.
+#[derive(Default)]
+struct SortKeys {
+    /// Atoms of every column's pattern that the keys were computed for
+    atoms: Vec<Vec<Atom>>,
+    /// Keys with the indices of their items, in sorted order
+    keys: Vec<([u32; 5], u32)>,
+    /// Whether the item at each index has a key
+    has_key: Vec<bool>,
+}
+
 pub struct Searcher {
-    nucleo: Nucleo<String>,
+    nucleo: Nucleo<Item>,
+    matcher: Matcher,
     source: Arc<SearcherSource>,
-    last_pattern: Option<String>,
+    format: Arc<ItemFormat>,
+    options: MatchOptions,
+    sort: SortOptions,
+    // Positions of nucleo's matches in the order they're shown, if it differs
+    // from nucleo's own order
+    order: Option<Vec<u32>>,
+    order_stale: bool,
+    sort_keys: SortKeys,
+    source_status: Sender<SourceStatus>,
+    injection: Option<JoinHandle<()>>,
+    draw_sender: Sender<()>,
+    query: String,
+    // Last pattern of every column
+    last_patterns: Vec<Option<String>>,
+    finished: bool,
 }
 
 impl Searcher {
-    pub fn new(source: SearcherSource, draw_sender: Sender<()>) -> Self {
+    pub fn new(
+        source: SearcherSource,
+        context: &Context,
+        format: ItemFormat,
+        options: MatchOptions,
+        sort: SortOptions,
+        draw_sender: Sender<()>,
+    ) -> Self {
+        let column_count = format.column_count();
+        let notify_sender = draw_sender.clone();
         let nucleo = Nucleo::new(
             nucleo::Config::DEFAULT,
             Arc::new(move || {
-                let _ = draw_sender.send(());
+                let _ = notify_sender.send(());
             }),
             None,
-            1,
+            column_count,
         );
 
         let mut searcher = Searcher {
             nucleo,
+            matcher: Matcher::new(nucleo::Config::DEFAULT),
             source: Arc::new(source),
-            last_pattern: None,
+            format: Arc::new(format),
+            options,
+            sort,
+            order: None,
+            order_stale: true,
+            sort_keys: SortKeys::default(),
+            source_status: watch::Sender::default(),
+            injection: None,
+            draw_sender,
+            query: String::new(),
+            last_patterns: vec![None; column_count as usize],
+            finished: false,
         };
         searcher.search("");
-        searcher.init();
+        searcher.init(context);
         searcher
     }
 
-    pub fn init(&mut self) {
+    /// Starts injecting items, where placeholders of the source command are
+    /// replaced with values from the context.
+    pub fn init(&mut self, context: &Context) {
         let source = Arc::clone(&self.source);
         let injector = self.nucleo.injector();
-        source.inject(injector);
+        self.source_status = watch::Sender::default();
+        self.injection = Some(source.inject(
+            context,
+            injector,
+            Arc::clone(&self.format),
+            self.source_status.clone(),
+            self.draw_sender.clone(),
+        ));
     }
 
-    pub fn search(&mut self, pattern: &str) {
-        if self
-            .last_pattern
-            .as_ref()
-            .is_some_and(|last_pattern| pattern == last_pattern)
-        {
-            return;
+    /// Clears all items and injects them again, unless they were read from
+    /// stdin, which can't be read twice. Returns whether items are reloaded.
+    pub fn reload(&mut self, context: &Context) -> bool {
+        if let SearcherSource::Stdin = *self.source {
+            self.show_error("cannot reload stdin".to_string());
+            return false;
         }
+        if let Some(injection) = self.injection.take() {
+            injection.abort();
+        }
+        self.nucleo.restart(true);
+        self.order_stale = true;
+        self.init(context);
+        true
+    }
 
-        self.nucleo.pattern.reparse(
-            0,
-            pattern,
-            CaseMatching::Smart,
-            Normalization::Smart,
-            self.last_pattern
+    /// Checks if every item had been injected and matched during the last tick.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Waits until every item has been injected and matched.
+    pub async fn finish(&mut self) {
+        self.tick();
+        while !self.finished {
+            sleep(Duration::from_millis(10)).await;
+            self.tick();
+        }
+    }
+
+    /// Shows an error where the source's errors are shown.
+    pub fn show_error(&self, error: String) {
+        self.source_status
+            .send_modify(|status| status.stderr = Some(error));
+    }
+
+    pub fn source_status(&self) -> SourceStatus {
+        self.source_status.borrow().clone()
+    }
+
+    pub fn search(&mut self, query: &str) {
+        query.clone_into(&mut self.query);
+        let patterns = self.format.split_query(query);
+        for (column, pattern) in patterns.into_iter().enumerate() {
+            let pattern = self.options.algorithm.apply(&pattern);
+            let last_pattern = &mut self.last_patterns[column];
+            if last_pattern
                 .as_ref()
-                .is_some_and(|last_pattern| pattern.starts_with(last_pattern)),
-        );
-        self.last_pattern = Some(pattern.to_string());
+                .is_some_and(|last_pattern| &pattern == last_pattern)
+            {
+                continue;
+            }
+
+            self.nucleo.pattern.reparse(
+                column,
+                &pattern,
+                self.options.case.into(),
+                self.options.normalization.into(),
+                last_pattern
+                    .as_ref()
+                    .is_some_and(|last_pattern| pattern.starts_with(last_pattern)),
+            );
+            *last_pattern = Some(pattern);
+        }
+    }
+
+    pub fn options(&self) -> MatchOptions {
+        self.options
+    }
+
+    /// Changes the match options and searches for the current query again.
+    pub fn set_options(&mut self, options: MatchOptions) {
+        self.options = options;
+        self.last_patterns.fill(None);
+        self.search(&self.query.clone());
+    }
+
+    pub fn is_sorted(&self) -> bool {
+        self.sort.sort
+    }
+
+    /// Switches between sorting by score and keeping input order.
+    pub fn toggle_sort(&mut self) {
+        self.sort.sort = !self.sort.sort;
+        self.order_stale = true;
     }
 
     pub fn tick(&mut self) {
-        self.nucleo.tick(10);
+        // Checked before ticking so items injected in the meantime are matched
+        let injected = self.injection.as_ref().is_none_or(JoinHandle::is_finished);
+        let status = self.nucleo.tick(10);
+        self.finished = injected && !status.running;
+        if status.changed || self.order_stale {
+            if self.order_stale {
+                self.sort_keys = SortKeys::default();
+            }
+            self.order = self.sort_order();
+            self.order_stale = false;
+        }
+    }
+
+    fn sort_order(&mut self) -> Option<Vec<u32>> {
+        if self.sort.is_default() {
+            return None;
+        }
+
+        let snapshot = self.nucleo.snapshot();
+        let pattern = snapshot.pattern();
+        // The snapshot's pattern lags behind searches until they're matched
+        let atoms: Vec<_> = (0..self.last_patterns.len())
+            .map(|column| pattern.column_pattern(column).atoms.clone())
+            .collect();
+        let sort_keys = &mut self.sort_keys;
+        if sort_keys.atoms != atoms {
+            *sort_keys = SortKeys {
+                atoms,
+                ..SortKeys::default()
+            };
+        }
+
+        let item_count = snapshot.item_count() as usize;
+        sort_keys.has_key.resize(item_count, false);
+        let mut positions = vec![None; item_count];
+        let mut new_keys = Vec::new();
+        let mut indices = Vec::new();
+        for (pos, item) in snapshot.matched_items(..).enumerate() {
+            let index = item.data.index;
+            positions[index as usize] = Some(pos as u32);
+            if !mem::replace(&mut sort_keys.has_key[index as usize], true) {
+                let key = sort_key(item, pattern, &self.sort, &mut self.matcher, &mut indices);
+                new_keys.push((key, index));
+            }
+        }
+        // Merging two sorted runs is linear with a stable sort
+        new_keys.sort_unstable();
+        sort_keys.keys.append(&mut new_keys);
+        sort_keys.keys.sort();
+        Some(
+            sort_keys
+                .keys
+                .iter()
+                .filter_map(|&(_, index)| positions[index as usize])
+                .collect(),
+        )
+    }
+
+    fn matched_item(&self, pos: usize) -> Option<nucleo::Item<'_, Item>> {
+        matched_item(self.nucleo.snapshot(), self.order.as_deref(), pos)
+    }
+
+    pub fn item_count(&self) -> usize {
+        self.nucleo.snapshot().item_count() as usize
     }
 
     pub fn result_count(&self) -> usize {
         self.nucleo.snapshot().matched_item_count() as usize
     }
 
-    pub fn results(&self, offset: usize, height: u16) -> Vec<String> {
-        let (offset, height) = (offset as u32, height as u32);
-        let snapshot = self.nucleo.snapshot();
-        let max = snapshot.matched_item_count();
-        snapshot
-            .matched_items(offset.min(max)..(offset + height).min(max))
+    pub fn result(&self, index: usize) -> Option<Item> {
+        self.matched_item(index).map(|item| item.data.clone())
+    }
+
+    pub fn position(&self, text: &str) -> Option<usize> {
+        (0..self.result_count()).position(|pos| {
+            self.matched_item(pos)
+                .is_some_and(|item| item.data.text == text)
+        })
+    }
+
+    pub fn item(&self, index: u32) -> Option<Item> {
+        self.nucleo
+            .snapshot()
+            .get_item(index)
             .map(|item| item.data.clone())
+    }
+
+    pub fn matched_indices(&self) -> Vec<u32> {
+        self.nucleo
+            .snapshot()
+            .matched_items(..)
+            .map(|item| item.data.index)
+            .collect()
+    }
+
+    pub fn results(&mut self, offset: usize, height: u16) -> Vec<SearchResult> {
+        let snapshot = self.nucleo.snapshot();
+        let pattern = snapshot.pattern();
+        let max = snapshot.matched_item_count() as usize;
+        (offset.min(max)..(offset + height as usize).min(max))
+            .filter_map(|pos| matched_item(snapshot, self.order.as_deref(), pos))
+            .map(|item| {
+                // Every column is highlighted with the words searched in it
+                let mut indices = Vec::new();
+                for (column, haystack) in item.matcher_columns.iter().enumerate() {
+                    let mut column_indices = Vec::new();
+                    pattern.column_pattern(column).indices(
+                        haystack.slice(..),
+                        &mut self.matcher,
+                        &mut column_indices,
+                    );
+                    indices.extend(
+                        self.format
+                            .display_indices(item.data, column, column_indices),
+                    );
+                }
+                indices.sort_unstable();
+                indices.dedup();
+                SearchResult {
+                    item: item.data.clone(),
+                    indices,
+                }
+            })
             .collect()
     }
 }
 
+/// Returns the key that orders an item among others, compared in order, so the
+/// score comes first.
+fn sort_key(
+    item: nucleo::Item<'_, Item>,
+    pattern: &MultiPattern,
+    sort: &SortOptions,
+    matcher: &mut Matcher,
+    indices: &mut Vec<u32>,
+) -> [u32; 5] {
+    let mut key = [0; 5];
+    if !sort.sort {
+        key[0] = item.data.index;
+        return key;
+    }
+    key[0] = u32::MAX - pattern.score(item.matcher_columns, matcher).unwrap_or(0);
+
+    // Matches are positioned in the first column that's searched
+    let column = (0..item.matcher_columns.len())
+        .find(|&column| !pattern.column_pattern(column).atoms.is_empty())
+        .unwrap_or(0);
+    let haystack = item.matcher_columns[column].slice(..);
+    indices.clear();
+    if sort.needs_indices() {
+        pattern
+            .column_pattern(column)
+            .indices(haystack, matcher, indices);
+    }
+    for (tiebreak, value) in sort.tiebreaks.iter().zip(&mut key[1..]) {
+        *value = match tiebreak {
+            Tiebreak::Length => item
+                .matcher_columns
+                .iter()
+                .map(|column| column.len() as u32)
+                .sum(),
+            Tiebreak::Begin => indices.iter().min().copied().unwrap_or(0),
+            Tiebreak::End => indices
+                .iter()
+                .max()
+                .map_or(0, |&end| haystack.len() as u32 - end - 1),
+            Tiebreak::Index => item.data.index,
+        };
+    }
+    key
+}
+
+fn matched_item<'a>(
+    snapshot: &'a Snapshot<Item>,
+    order: Option<&[u32]>,
+    pos: usize,
+) -> Option<nucleo::Item<'a, Item>> {
+    let pos = match order {
+        Some(order) => *order.get(pos)?,
+        None => pos as u32,
+    };
+    snapshot.get_matched_item(pos)
+}
+
+impl Drop for Searcher {
+    fn drop(&mut self) {
+        if let Some(injection) = &self.injection {
+            injection.abort();
+        }
+    }
+}
+
 pub async fn debounce_draws(mut draw_receiver: Receiver<()>, sender: UnboundedSender<Message>) {
     while draw_receiver.changed().await.is_ok() {
         let _ = sender.send(Action::Draw.into());
         sleep(Duration::from_secs_f32(1.0 / 60.0)).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{item::ItemFormat, types::sorting::Tiebreak};
+
+    use super::*;
+
+    async fn filter(
+        items: &str,
+        query: &str,
+        format: ItemFormat,
+        sort: SortOptions,
+    ) -> Vec<String> {
+        let source = SearcherSource::Command(Template::parse(&format!("printf '{items}'")));
+        let (draw_sender, _) = watch::channel(());
+        let mut searcher = Searcher::new(
+            source,
+            &Context::default(),
+            format,
+            MatchOptions::default(),
+            sort,
+            draw_sender,
+        );
+        searcher.search(query);
+        searcher.finish().await;
+        (0..searcher.result_count())
+            .filter_map(|pos| searcher.result(pos))
+            .map(|item| item.text)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn ranking() {
+        let items = "xaxbx\\nab\\nabc\\nc";
+        assert_eq!(
+            filter(items, "ab", ItemFormat::default(), SortOptions::default()).await,
+            ["ab", "abc", "xaxbx"]
+        );
+        let sort = SortOptions {
+            sort: false,
+            ..SortOptions::default()
+        };
+        assert_eq!(
+            filter(items, "ab", ItemFormat::default(), sort).await,
+            ["xaxbx", "ab", "abc"]
+        );
+    }
+
+    #[tokio::test]
+    async fn tiebreaks() {
+        let items = "zzabzz\\nzzzzab";
+        let sort = |tiebreak| SortOptions {
+            sort: true,
+            tiebreaks: vec![tiebreak],
+        };
+        assert_eq!(
+            filter(items, "ab", ItemFormat::default(), sort(Tiebreak::Begin)).await,
+            ["zzabzz", "zzzzab"]
+        );
+        assert_eq!(
+            filter(items, "ab", ItemFormat::default(), sort(Tiebreak::End)).await,
+            ["zzzzab", "zzabzz"]
+        );
+        let format = ItemFormat::new(None, None, None, vec!["last=-1".parse().unwrap()]);
+        assert_eq!(
+            filter(
+                "a zzzzab\\nb zzabzz",
+                "last:ab",
+                format,
+                sort(Tiebreak::Begin)
+            )
+            .await,
+            ["b zzabzz", "a zzzzab"]
+        );
+    }
+
+    #[tokio::test]
+    async fn column_highlight() {
+        let source = SearcherSource::Command(Template::parse(r"printf 'lib x\nx lib'"));
+        let format = ItemFormat::new(None, None, None, vec!["last=-1".parse().unwrap()]);
+        let (draw_sender, _) = watch::channel(());
+        let mut searcher = Searcher::new(
+            source,
+            &Context::default(),
+            format,
+            MatchOptions::default(),
+            SortOptions::default(),
+            draw_sender,
+        );
+        searcher.search("last:lib");
+        searcher.finish().await;
+        let results = searcher.results(0, 10);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].item.text, "x lib");
+        assert_eq!(results[0].indices, [2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn separator() {
+        let items = "a\\nb\\0c\\r\\n\\0d";
+        let format = ItemFormat::default().with_separator("\0".to_string());
+        assert_eq!(
+            filter(items, "", format, SortOptions::default()).await,
+            ["a\nb", "c\r\n", "d"]
+        );
+        let format = ItemFormat::default().with_separator("::".to_string());
+        assert_eq!(
+            filter("a:b::c::", "", format, SortOptions::default()).await,
+            ["a:b", "c"]
+        );
+        assert_eq!(
+            filter(
+                "a\\r\\nb",
+                "",
+                ItemFormat::default(),
+                SortOptions::default()
+            )
+            .await,
+            ["a", "b"]
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_utf8() {
+        let source = SearcherSource::Command(Template::parse(r"printf 'a\377b\nc'"));
+        let (draw_sender, _) = watch::channel(());
+        let mut searcher = Searcher::new(
+            source,
+            &Context::default(),
+            ItemFormat::default(),
+            MatchOptions::default(),
+            SortOptions::default(),
+            draw_sender,
+        );
+        searcher.search("ab");
+        searcher.finish().await;
+        let item = searcher.result(0).unwrap();
+        assert_eq!(item.text, "a\u{fffd}b");
+        assert_eq!(item.bytes(), b"a\xffb");
+        assert_eq!(searcher.result_count(), 1);
+        assert_eq!(searcher.source_status().to_string(), "invalid UTF-8: 1");
+    }
+}
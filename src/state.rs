@@ -1,9 +1,29 @@
+use std::process::ExitCode;
+
 use anyhow::Result;
 use crossterm::terminal;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExitStatus {
+    Accepted,
+    NoMatch,
+    Aborted,
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(value: ExitStatus) -> Self {
+        match value {
+            ExitStatus::Accepted => ExitCode::SUCCESS,
+            ExitStatus::NoMatch => ExitCode::from(1),
+            ExitStatus::Aborted => ExitCode::from(130),
+        }
+    }
+}
+
+#[derive(Default)]
 pub struct State {
     terminal_size: (u16, u16),
-    running: bool,
+    exit_status: Option<ExitStatus>,
     skip_frame: bool,
 }
 
@@ -11,7 +31,7 @@ impl State {
     pub fn new() -> Result<Self> {
         Ok(State {
             terminal_size: terminal::size()?,
-            running: true,
+            exit_status: None,
             skip_frame: false,
         })
     }
@@ -20,12 +40,20 @@ impl State {
         self.terminal_size
     }
 
+    pub fn set_terminal_size(&mut self, size: (u16, u16)) {
+        self.terminal_size = size;
+    }
+
     pub fn running(&self) -> bool {
-        self.running
+        self.exit_status.is_none()
+    }
+
+    pub fn exit(&mut self, status: ExitStatus) {
+        self.exit_status = Some(status);
     }
 
-    pub fn exit(&mut self) {
-        self.running = false;
+    pub fn exit_status(&self) -> ExitStatus {
+        self.exit_status.unwrap_or(ExitStatus::Aborted)
     }
 
     pub fn skip_frame(&mut self) {
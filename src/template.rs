@@ -0,0 +1,354 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    mem,
+    os::unix::ffi::OsStringExt,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{item::Item, types::fields::Fields, utils};
+
+/// What a command's placeholders are replaced with.
+#[derive(Clone, Debug)]
+pub struct Context {
+    pub selected: Option<Item>,
+    /// Marked items, or the selected item if none are marked
+    pub accepted: Vec<Item>,
+    pub query: String,
+    pub delimiter: Option<String>,
+    /// What follows each item in files, like in tez's output
+    pub separator: String,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Context {
+            selected: None,
+            accepted: Vec::new(),
+            query: String::new(),
+            delimiter: None,
+            separator: "\n".to_string(),
+        }
+    }
+}
+
+/// Part of an item that a placeholder is replaced with.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    /// The whole item, `{}`
+    Item,
+    /// The index of the item, `{n}`
+    Index,
+    /// Fields of the item, e.g. `{1}` or `{2..}`
+    Fields(Fields),
+    /// A temporary file that contains the item, `{f}`
+    File,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Text(String),
+    /// `{q}`
+    Query,
+    /// A value of the selected item, or of every accepted item if `all` is
+    /// set with a `+`, e.g. `{+}` or `{+1}`
+    Placeholder {
+        value: Value,
+        all: bool,
+    },
+}
+
+/// A command with placeholders that are replaced with items and the query.
+/// Braces that don't form a placeholder are kept as they are.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template(Vec<Segment>);
+
+impl Template {
+    pub fn parse(s: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut rest = s;
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            rest = &rest[start..];
+            match rest
+                .find('}')
+                .and_then(|end| Some((end, parse_placeholder(&rest[1..end])?)))
+            {
+                Some((end, segment)) => {
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(mem::take(&mut text)));
+                    }
+                    segments.push(segment);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Template(segments)
+    }
+
+    /// Checks if a placeholder is replaced with every accepted item.
+    pub fn uses_accepted(&self) -> bool {
+        self.0
+            .iter()
+            .any(|segment| matches!(segment, Segment::Placeholder { all: true, .. }))
+    }
+
+    /// Checks if a placeholder refers to items the context doesn't have, like
+    /// the selected item when nothing matched.
+    pub fn is_missing_items(&self, context: &Context) -> bool {
+        self.0.iter().any(|segment| match segment {
+            Segment::Placeholder { all: true, .. } => context.accepted.is_empty(),
+            Segment::Placeholder { all: false, .. } => context.selected.is_none(),
+            _ => false,
+        })
+    }
+
+    /// Replaces placeholders with values from the context. Every value is
+    /// quoted so the shell sees it as a single word. Values of missing items
+    /// are empty strings.
+    pub fn expand(&self, context: &Context) -> io::Result<String> {
+        let mut expanded = String::new();
+        for segment in &self.0 {
+            match segment {
+                Segment::Text(text) => expanded.push_str(text),
+                Segment::Query => expanded.push_str(&utils::shell_quote(&context.query)),
+                Segment::Placeholder { value, all } => {
+                    let items = if *all {
+                        &context.accepted[..]
+                    } else {
+                        context.selected.as_slice()
+                    };
+                    if items.is_empty() {
+                        expanded.push_str(&utils::shell_quote(""));
+                    } else {
+                        expanded.push_str(&value.expand(items, context)?);
+                    }
+                }
+            }
+        }
+        Ok(expanded)
+    }
+}
+
+impl Value {
+    /// Returns the value of each item, separated by spaces. Files contain
+    /// every item, so there's only one.
+    fn expand(&self, items: &[Item], context: &Context) -> io::Result<String> {
+        if *self == Value::File {
+            return Ok(utils::shell_quote(
+                &write_temp_file(items, &context.separator)?.to_string_lossy(),
+            ));
+        }
+        let delimiter = context.delimiter.as_deref();
+        let words: Vec<_> = items
+            .iter()
+            .map(|item| match self {
+                Value::Index => item.index.to_string(),
+                Value::Fields(fields) => utils::shell_quote(&fields.select(&item.text, delimiter)),
+                _ => utils::shell_quote(&item.text),
+            })
+            .collect();
+        Ok(words.join(" "))
+    }
+}
+
+/// Parses what's between the braces of a placeholder.
+fn parse_placeholder(s: &str) -> Option<Segment> {
+    if s == "q" {
+        return Some(Segment::Query);
+    }
+    let (all, s) = match s.strip_prefix('+') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+    let value = match s {
+        "" => Value::Item,
+        "n" => Value::Index,
+        "f" => Value::File,
+        s if s.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') => {
+            Value::Fields(s.parse().ok()?)
+        }
+        _ => None?,
+    };
+    Some(Segment::Placeholder { value, all })
+}
+
+/// Directory temporary files are written to, and the number of files in it.
+static TEMP_DIR: Mutex<Option<(PathBuf, u32)>> = Mutex::new(None);
+
+/// Creates a directory only the user can access, with a random name so no one
+/// else can have created it first.
+fn create_temp_dir() -> io::Result<PathBuf> {
+    let mut template = env::temp_dir()
+        .join("tez-XXXXXX")
+        .into_os_string()
+        .into_vec();
+    template.push(0);
+    // SAFETY: The template is a NUL-terminated string that outlives the call
+    if unsafe { libc::mkdtemp(template.as_mut_ptr().cast()) }.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    template.pop();
+    Ok(OsString::from_vec(template).into())
+}
+
+/// Writes items to a new temporary file, each followed by the separator, and
+/// returns its path.
+fn write_temp_file(items: &[Item], separator: &str) -> io::Result<PathBuf> {
+    let path = {
+        let mut temp_dir = TEMP_DIR.lock().unwrap_or_else(PoisonError::into_inner);
+        if temp_dir.is_none() {
+            *temp_dir = Some((create_temp_dir()?, 0));
+        }
+        let (dir, count) = temp_dir.as_mut().expect("temporary directory was created");
+        *count += 1;
+        dir.join(count.to_string())
+    };
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    for item in items {
+        file.write_all(item.bytes())?;
+        file.write_all(separator.as_bytes())?;
+    }
+    Ok(path)
+}
+
+/// Prefixes a command with a trap that removes the temporary files written
+/// while expanding templates once the shell exits, for commands that outlive
+/// tez.
+pub fn remove_temp_files_on_exit(command: &str) -> String {
+    let temp_dir = TEMP_DIR.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some((dir, _)) = &*temp_dir else {
+        return command.to_string();
+    };
+    let remove = format!("rm -rf {}", utils::shell_quote(&dir.to_string_lossy()));
+    format!("trap {} EXIT; {command}", utils::shell_quote(&remove))
+}
+
+/// Removes the temporary files written while expanding templates when it's
+/// dropped, so they're also removed when tez exits with an error.
+pub struct TempFiles;
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        let temp_dir = TEMP_DIR
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some((dir, _)) = temp_dir {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use crate::item::ItemFormat;
+
+    use super::*;
+
+    fn context() -> Context {
+        let format = ItemFormat::new(None, None, None, Vec::new());
+        let items = vec![
+            format.item(0, "a b".to_string()),
+            format.item(1, "it's c".to_string()),
+        ];
+        Context {
+            selected: Some(items[1].clone()),
+            accepted: items,
+            query: "q".to_string(),
+            delimiter: None,
+            separator: "\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Template::parse("a {} {+2} {x} {").0,
+            [
+                Segment::Text("a ".to_string()),
+                Segment::Placeholder {
+                    value: Value::Item,
+                    all: false
+                },
+                Segment::Text(" ".to_string()),
+                Segment::Placeholder {
+                    value: Value::Fields("2".parse().unwrap()),
+                    all: true
+                },
+                Segment::Text(" {x} {".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn expand() {
+        let expand = |s| Template::parse(s).expand(&context()).unwrap();
+        assert_eq!(expand("cmd {} {q}"), r"cmd 'it'\''s c' 'q'");
+        assert_eq!(expand("{+} {+n} {n}"), r"'a b' 'it'\''s c' 0 1 1");
+        assert_eq!(expand("{1} {+2..}"), r"'it'\''s' 'b' 'c'");
+        assert_eq!(
+            expand("awk '{print $1}' ${HOME}"),
+            "awk '{print $1}' ${HOME}"
+        );
+        assert_eq!(
+            Template::parse("{} {+}")
+                .expand(&Context::default())
+                .unwrap(),
+            "'' ''"
+        );
+    }
+
+    #[test]
+    fn expand_file() {
+        let _temp_files = TempFiles;
+        let read = |context| {
+            let path = Template::parse("{+f}").expand(&context).unwrap();
+            fs::read_to_string(path.trim_matches('\'')).unwrap()
+        };
+        assert_eq!(read(context()), "a b\nit's c\n");
+        let context = Context {
+            separator: "\0".to_string(),
+            ..context()
+        };
+        assert_eq!(read(context.clone()), "a b\0it's c\0");
+        // Every expansion writes a file of its own
+        assert_eq!(read(context), "a b\0it's c\0");
+
+        let (dir, count) = TEMP_DIR.lock().unwrap().clone().unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(
+            fs::metadata(dir).unwrap().permissions().mode() & 0o777,
+            0o700
+        );
+    }
+
+    #[test]
+    fn missing_items() {
+        let context = Context {
+            query: "q".to_string(),
+            ..Context::default()
+        };
+        assert!(!Template::parse("cmd {q}").is_missing_items(&context));
+        assert!(Template::parse("cmd {}").is_missing_items(&context));
+        assert!(Template::parse("cmd {+n}").is_missing_items(&context));
+        assert!(!Template::parse("cmd {+n}").is_missing_items(&self::context()));
+    }
+}
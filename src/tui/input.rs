@@ -1,23 +1,91 @@
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
+    layout::{Margin, Rect},
     widgets::{Block, Widget},
 };
 use tui_textarea::{CursorMove, TextArea};
+use unicode_width::UnicodeWidthChar;
 
-use crate::types::action::InputAction;
+use crate::types::{action::InputAction, theme::Palette};
+
+const MAX_LINES: usize = 5;
 
 pub struct Input<'a> {
     text_area: TextArea<'a>,
 }
 
 impl Input<'_> {
-    pub fn new() -> Self {
-        let mut text_area = TextArea::from([""]);
-        text_area.set_block(Block::bordered());
+    pub fn new(query: &str, borders: bool, palette: Palette) -> Self {
+        let mut text_area = TextArea::from([query]);
+        text_area.set_style(palette.prompt);
+        if borders {
+            text_area.set_block(Block::bordered().border_style(palette.border));
+        }
+        text_area.move_cursor(CursorMove::End);
         Input { text_area }
     }
 
+    /// Returns the height needed to show every line, up to a limit.
+    pub fn height(&self) -> u16 {
+        let lines = self.text_area.lines().len().min(MAX_LINES) as u16;
+        let borders = if self.text_area.block().is_some() {
+            2
+        } else {
+            0
+        };
+        lines + borders
+    }
+
+    /// Returns the line the cursor is on, which is the one that's searched.
+    pub fn query(&self) -> &str {
+        self.text_area
+            .lines()
+            .get(self.text_area.cursor().0)
+            .map_or("", |s| s.as_str())
+    }
+
+    /// Inserts text at the cursor, where each newline starts a new line of the
+    /// query. A trailing newline is ignored, since most copied lines end with
+    /// one. Returns the line the cursor ends up on.
+    pub fn paste(&mut self, text: &str) -> Option<&str> {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+        if !self.text_area.insert_str(text) {
+            return None;
+        }
+        self.text_area
+            .lines()
+            .get(self.text_area.cursor().0)
+            .map(|s| s.as_str())
+    }
+
+    /// Moves the cursor to a cell of the area the input was rendered in. Lines
+    /// are assumed not to be scrolled. Returns the line the cursor is on if it
+    /// changed lines.
+    pub fn jump(&mut self, area: Rect, column: u16, row: u16) -> Option<&str> {
+        let area = match self.text_area.block() {
+            Some(_) => area.inner(Margin::new(1, 1)),
+            None => area,
+        };
+        if !(area.top()..area.bottom()).contains(&row) {
+            return None;
+        }
+        let line_index = (row - area.top()) as usize;
+        let line = self.text_area.lines().get(line_index)?;
+        let mut width = 0;
+        let col = line
+            .chars()
+            .take_while(|c| {
+                width += c.width().unwrap_or(0);
+                width <= column.saturating_sub(area.left()) as usize
+            })
+            .count();
+        let previous_line = self.text_area.cursor().0;
+        self.text_area
+            .move_cursor(CursorMove::Jump(line_index as u16, col as u16));
+        (line_index != previous_line).then(|| self.text_area.lines()[line_index].as_str())
+    }
+
     #[rustfmt::skip]
     pub fn handle_action(&mut self, action: &InputAction) -> Option<&str> {
         if match action {
@@ -34,6 +102,7 @@ impl Input<'_> {
             InputAction::MoveToBottom => { self.text_area.move_cursor(CursorMove::Bottom); true },
             InputAction::MoveToHead => { self.text_area.move_cursor(CursorMove::Head); false },
             InputAction::MoveToEnd => { self.text_area.move_cursor(CursorMove::End); false },
+            InputAction::Clear => { self.text_area.select_all(); self.text_area.cut() },
             InputAction::Delete => self.text_area.delete_char(),
             InputAction::DeleteNext => self.text_area.delete_next_char(),
             InputAction::DeleteWord => self.text_area.delete_word(),
@@ -56,3 +125,40 @@ impl Widget for &Input<'_> {
         self.text_area.render(area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paste() {
+        let mut input = Input::new("a", false, Palette::default());
+        assert_eq!(input.paste("b c\n"), Some("ab c"));
+        assert_eq!(input.paste("\r\nd\re"), Some("e"));
+        assert_eq!(input.text_area.lines(), ["ab c", "d", "e"]);
+        assert_eq!(input.paste(""), None);
+    }
+
+    #[test]
+    fn jump() {
+        let area = Rect::new(0, 0, 20, 4);
+        let mut input = Input::new("ab", true, Palette::default());
+        input.paste("\n日本語");
+        // Columns within the border are counted from the first character
+        assert_eq!(input.jump(area, 5, 2), None);
+        assert_eq!(input.text_area.cursor(), (1, 2));
+        assert_eq!(input.jump(area, 4, 2), None);
+        assert_eq!(input.text_area.cursor(), (1, 1));
+        assert_eq!(input.jump(area, 1, 1), Some("ab"));
+        assert_eq!(input.text_area.cursor(), (0, 0));
+        assert_eq!(input.jump(area, 1, 0), None);
+        assert_eq!(input.jump(area, 1, 3), None);
+        assert_eq!(input.text_area.cursor(), (0, 0));
+
+        let mut input = Input::new("日本", false, Palette::default());
+        assert_eq!(input.jump(area, 3, 0), None);
+        assert_eq!(input.text_area.cursor(), (0, 1));
+        assert_eq!(input.jump(area, 10, 0), None);
+        assert_eq!(input.text_area.cursor(), (0, 2));
+    }
+}
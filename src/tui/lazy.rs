@@ -3,7 +3,7 @@ use std::mem;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    widgets::{List, ListState, StatefulWidget},
+    widgets::{List, ListItem, ListState, StatefulWidget},
 };
 
 #[derive(Default, Clone, Copy)]
@@ -75,6 +75,10 @@ impl LazyState {
         self.pos = LazyPos::End(0);
     }
 
+    pub fn select(&mut self, pos: usize) {
+        self.pos = LazyPos::Start(pos);
+    }
+
     pub fn offset(&self) -> usize {
         self.offset
     }
@@ -93,16 +97,16 @@ impl LazyState {
 }
 
 pub struct LazyList<'a> {
-    builder: fn() -> List<'a>,
+    // List without items that's cloned to render the visible items
+    list: List<'a>,
     state: LazyState,
 }
 
 impl<'a> LazyList<'a> {
-    pub fn new(builder: fn() -> List<'a>) -> Self {
-        LazyList {
-            builder,
-            state: LazyState::default(),
-        }
+    pub fn new(list: List<'a>) -> Self {
+        let mut state = LazyState::default();
+        state.first();
+        LazyList { list, state }
     }
 
     pub fn next(&mut self) {
@@ -121,6 +125,10 @@ impl<'a> LazyList<'a> {
         self.state.last();
     }
 
+    pub fn select(&mut self, pos: usize) {
+        self.state.select(pos);
+    }
+
     pub fn update(&mut self, len: usize, height: u16) {
         self.state.update(len, height);
     }
@@ -128,13 +136,17 @@ impl<'a> LazyList<'a> {
     pub fn offset(&self) -> usize {
         self.state.offset()
     }
+
+    pub fn position(&self) -> Option<usize> {
+        self.state.position()
+    }
 }
 
-impl StatefulWidget for &mut LazyList<'_> {
-    type State = Vec<String>;
+impl<'a> StatefulWidget for &mut LazyList<'a> {
+    type State = Vec<ListItem<'a>>;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        let list = (self.builder)().items(mem::take(state));
+        let list = self.list.clone().items(mem::take(state));
         let mut list_state = ListState::default().with_selected(self.state.real_position());
         StatefulWidget::render(&list, area, buf, &mut list_state);
     }
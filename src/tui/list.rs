@@ -1,16 +1,28 @@
+use std::{borrow::Cow, mem, ops::Range};
+
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
-    style::Color,
+    layout::{Margin, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
     widgets::{
-        Block, List, ListItem, ListState, Scrollbar, ScrollbarState, StatefulWidget, Widget,
+        Block, Borders, List, ListDirection, ListItem, ListState, Scrollbar, ScrollbarState,
+        StatefulWidget, Widget,
     },
 };
 use tokio::sync::watch::Sender;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-use crate::searcher::{Searcher, SearcherSource};
+use crate::{
+    config::Config,
+    item::Item,
+    searcher::{SearchResult, Searcher},
+    template::Context,
+    types::{mark_order::MarkOrder, matching::MatchOptions, theme::Palette},
+};
 
-use super::lazy::LazyList;
+use super::{lazy::LazyList, marks::Marks, status::Counts};
 
 pub struct PlainList<'a> {
     list: List<'a>,
@@ -18,14 +30,14 @@ pub struct PlainList<'a> {
     scrollbar_state: ScrollbarState,
 }
 impl<'a> PlainList<'a> {
-    pub fn new<I>(items: I) -> Self
+    pub fn new<I>(items: I, palette: Palette) -> Self
     where
         I: Iterator,
         I::Item: Into<ListItem<'a>>,
     {
         let list = List::new(items)
-            .highlight_style(Color::Red)
-            .block(Block::bordered());
+            .highlight_style(palette.selected)
+            .block(Block::bordered().border_style(palette.border));
         let list_state = ListState::default().with_selected(Some(0));
         let scrollbar_state = ScrollbarState::new(list.len()).viewport_content_length(1);
         PlainList {
@@ -58,60 +70,422 @@ impl Widget for &mut PlainList<'_> {
     }
 }
 
+const MARKER_WIDTH: u16 = 2;
+
 pub struct SearchableList<'a> {
     searcher: Searcher,
     list: LazyList<'a>,
     scrollbar_state: ScrollbarState,
+    palette: Palette,
+    borders: bool,
+    reversed: bool,
+    marks: Marks,
+    // Item that was selected before a reload, which gets selected again once
+    // it's been injected
+    reselect: Option<String>,
 }
 
 impl SearchableList<'_> {
-    pub fn new(source: SearcherSource, draw_sender: Sender<()>) -> Self {
-        let list_builder = || {
-            List::default()
-                .highlight_style(Color::Red)
-                .block(Block::bordered())
+    pub fn new(config: &Config, context: &Context, draw_sender: Sender<()>) -> Self {
+        let palette = config.palette();
+        let borders = config.borders();
+        let direction = if config.layout().is_reversed() {
+            ListDirection::BottomToTop
+        } else {
+            ListDirection::TopToBottom
         };
+        let mut list = List::default()
+            .highlight_style(palette.selected)
+            .direction(direction);
+        if borders {
+            list = list.block(Block::bordered().border_style(palette.border));
+        }
         SearchableList {
-            searcher: Searcher::new(source, draw_sender),
-            list: LazyList::new(list_builder),
+            searcher: Searcher::new(
+                config.source(),
+                context,
+                config.item_format(),
+                config.match_options(),
+                config.sort_options(),
+                draw_sender,
+            ),
+            list: LazyList::new(list),
             scrollbar_state: ScrollbarState::default(),
+            palette,
+            borders,
+            reversed: config.layout().is_reversed(),
+            marks: Marks::default(),
+            reselect: None,
         }
     }
 
     pub fn next(&mut self) {
+        self.reselect = None;
         self.list.next();
         self.scrollbar_state.next();
     }
 
     pub fn previous(&mut self) {
+        self.reselect = None;
         self.list.previous();
         self.scrollbar_state.prev();
     }
 
     pub fn first(&mut self) {
+        self.reselect = None;
         self.list.first();
     }
 
     pub fn last(&mut self) {
+        self.reselect = None;
         self.list.last();
     }
 
+    pub fn select(&mut self, pos: usize) {
+        self.reselect = None;
+        self.list.select(pos);
+    }
+
+    /// Returns the position of the result shown on a row of the area the list
+    /// was rendered in.
+    pub fn position_at(&self, area: Rect, row: u16) -> Option<usize> {
+        let area = if self.borders {
+            area.inner(Margin::new(1, 1))
+        } else {
+            area
+        };
+        if !(area.top()..area.bottom()).contains(&row) {
+            return None;
+        }
+        let line = if self.reversed {
+            area.bottom() - 1 - row
+        } else {
+            row - area.top()
+        };
+        let pos = self.list.offset() + line as usize;
+        (pos < self.searcher.result_count()).then_some(pos)
+    }
+
+    /// Reloads items, returning whether the source could be read again.
+    pub fn reload(&mut self, context: &Context) -> bool {
+        let reselect = self.selected().map(|item| item.text);
+        if !self.searcher.reload(context) {
+            return false;
+        }
+        self.reselect = reselect;
+        // Item indices are only valid for a single load
+        self.marks.clear();
+        true
+    }
+
+    pub fn show_error(&self, error: String) {
+        self.searcher.show_error(error);
+    }
+
+    pub fn toggle_mark(&mut self) {
+        if let Some(item) = self.selected() {
+            self.marks.toggle(item.index);
+        }
+    }
+
+    /// Marks every item that matches the current query.
+    pub fn mark_all(&mut self) {
+        for index in self.searcher.matched_indices() {
+            self.marks.mark(index);
+        }
+    }
+
+    /// Unmarks every item that matches the current query.
+    pub fn unmark_all(&mut self) {
+        for index in self.searcher.matched_indices() {
+            self.marks.unmark(index);
+        }
+    }
+
+    /// Toggles the mark of every item that matches the current query.
+    pub fn toggle_all(&mut self) {
+        for index in self.searcher.matched_indices() {
+            self.marks.toggle(index);
+        }
+    }
+
     pub fn search(&mut self, s: &str) {
         self.searcher.search(s);
     }
+
+    pub fn is_finished(&self) -> bool {
+        self.searcher.is_finished()
+    }
+
+    /// Waits until every item has been read and matched.
+    pub async fn finish(&mut self) {
+        self.searcher.finish().await;
+    }
+
+    pub fn result_count(&self) -> usize {
+        self.searcher.result_count()
+    }
+
+    pub fn result(&self, pos: usize) -> Option<Item> {
+        self.searcher.result(pos)
+    }
+
+    pub fn counts(&self) -> Counts {
+        Counts {
+            matched: self.searcher.result_count(),
+            total: self.searcher.item_count(),
+            marked: self.marks.len(),
+            loading: !self.searcher.is_finished(),
+        }
+    }
+
+    pub fn match_options(&self) -> MatchOptions {
+        self.searcher.options()
+    }
+
+    pub fn cycle_case(&mut self) {
+        let mut options = self.searcher.options();
+        options.case = options.case.next();
+        self.searcher.set_options(options);
+    }
+
+    pub fn cycle_algorithm(&mut self) {
+        let mut options = self.searcher.options();
+        options.algorithm = options.algorithm.next();
+        self.searcher.set_options(options);
+    }
+
+    pub fn is_sorted(&self) -> bool {
+        self.searcher.is_sorted()
+    }
+
+    pub fn toggle_sort(&mut self) {
+        self.reselect = self.selected().map(|item| item.text);
+        self.searcher.toggle_sort();
+    }
+
+    pub fn selected(&self) -> Option<Item> {
+        self.list
+            .position()
+            .and_then(|pos| self.searcher.result(pos))
+    }
+
+    /// Returns the marked items, or the selected item if none are marked.
+    pub fn accepted(&self, order: MarkOrder) -> Vec<Item> {
+        if self.marks.is_empty() {
+            self.selected().into_iter().collect()
+        } else {
+            self.marks
+                .indices(order)
+                .into_iter()
+                .filter_map(|index| self.searcher.item(index))
+                .collect()
+        }
+    }
+
+    fn list_item(&self, result: SearchResult, width: u16) -> ListItem<'static> {
+        let marker = if self.marks.contains(result.item.index) {
+            Span::styled("▌ ", self.palette.marker)
+        } else {
+            Span::from("  ")
+        };
+        let mut spans = vec![marker];
+        spans.extend(highlight(
+            result.item.display(),
+            result.item.styles(),
+            &result.indices,
+            width as usize,
+            self.palette.matched,
+        ));
+        Line::from(spans).into()
+    }
+}
+
+/// Splits text into spans where matched graphemes are styled, on top of the
+/// styles the text already has. If the last match wouldn't fit within the
+/// width, the start of the text is cut off and replaced with an ellipsis.
+fn highlight(
+    text: &str,
+    styles: &[(Range<usize>, Style)],
+    indices: &[u32],
+    width: usize,
+    match_style: Style,
+) -> Vec<Span<'static>> {
+    let graphemes: Vec<_> = text
+        .grapheme_indices(true)
+        .map(|(byte, grapheme)| (byte, escape_control(grapheme)))
+        .collect();
+    let mut start = 0;
+    if let Some(&last) = indices.last() {
+        let last = (last as usize).min(graphemes.len().saturating_sub(1));
+        let mut match_width: usize = graphemes[..=last].iter().map(|(_, g)| g.width()).sum();
+        if match_width > width {
+            // Leave room for the ellipsis
+            while start < last && match_width + 1 > width {
+                match_width -= graphemes[start].1.width();
+                start += 1;
+            }
+        }
+    }
+
+    let mut spans = Vec::new();
+    if start > 0 {
+        spans.push(Span::from("…"));
+    }
+    let mut indices = indices.iter().map(|&i| i as usize).peekable();
+    let mut styles = styles.iter().peekable();
+    let mut span = String::new();
+    let mut span_style = Style::new();
+    for (i, (byte, grapheme)) in graphemes.iter().enumerate() {
+        let matched = indices.next_if_eq(&i).is_some();
+        if i < start {
+            continue;
+        }
+        while styles.next_if(|(range, _)| range.end <= *byte).is_some() {}
+        let mut style = styles
+            .peek()
+            .filter(|(range, _)| range.contains(byte))
+            .map_or(Style::new(), |(_, style)| *style);
+        if matched {
+            style = style.patch(match_style);
+        }
+        if style != span_style && !span.is_empty() {
+            spans.push(Span::styled(mem::take(&mut span), span_style));
+        }
+        span_style = style;
+        span.push_str(grapheme);
+    }
+    if !span.is_empty() {
+        spans.push(Span::styled(span, span_style));
+    }
+    spans
+}
+
+/// Replaces control characters with their Unicode control pictures, so they
+/// can't affect the terminal. Tabs are replaced with spaces.
+fn escape_control(grapheme: &str) -> Cow<'_, str> {
+    if !grapheme.contains(char::is_control) {
+        return grapheme.into();
+    }
+    grapheme
+        .chars()
+        .map(|c| match c {
+            '\t' => ' ',
+            '\x7f' => '␡',
+            c if c.is_ascii_control() => char::from_u32(0x2400 + c as u32).unwrap_or('�'),
+            c if c.is_control() => '�',
+            c => c,
+        })
+        .collect::<String>()
+        .into()
 }
 
 impl Widget for &mut SearchableList<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         self.searcher.tick();
 
+        if let Some(item) = &self.reselect {
+            if let Some(pos) = self.searcher.position(item) {
+                self.list.select(pos);
+                self.reselect = None;
+            } else if self.searcher.is_finished() {
+                self.reselect = None;
+            }
+        }
+
         let len = self.searcher.result_count();
-        let height = area.height.saturating_sub(2);
+        let border_size = if self.borders { 2 } else { 0 };
+        let height = area.height.saturating_sub(border_size);
+        let width = area.width.saturating_sub(border_size + MARKER_WIDTH);
         self.list.update(len, height);
-        let mut results = self.searcher.results(self.list.offset(), height);
-        self.list.render(area, buf, &mut results);
+        let mut items = self
+            .searcher
+            .results(self.list.offset(), height)
+            .into_iter()
+            .map(|result| self.list_item(result, width))
+            .collect();
+        self.list.render(area, buf, &mut items);
+
+        let source_status = self.searcher.source_status();
+        if !source_status.is_empty() {
+            Block::new()
+                .borders(if self.borders {
+                    Borders::ALL
+                } else {
+                    Borders::NONE
+                })
+                .title_bottom(Line::from(source_status.to_string()).red())
+                .render(area, buf);
+        }
 
         let scrollbar = Scrollbar::default();
         scrollbar.render(area, buf, &mut self.scrollbar_state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::watch;
+
+    use super::*;
+
+    async fn seq_list(args: &[&str]) -> SearchableList<'static> {
+        let config = Config::parse_from(&[&["--command", "seq 10"], args].concat());
+        let (draw_sender, _) = watch::channel(());
+        let mut list = SearchableList::new(&config, &Context::default(), draw_sender);
+        list.finish().await;
+        list
+    }
+
+    #[tokio::test]
+    async fn position_at() {
+        let area = Rect::new(0, 2, 10, 5);
+        let list = seq_list(&[]).await;
+        assert_eq!(list.position_at(area, 2), None);
+        assert_eq!(list.position_at(area, 3), Some(0));
+        assert_eq!(list.position_at(area, 5), Some(2));
+        assert_eq!(list.position_at(area, 6), None);
+
+        let list = seq_list(&["--no-border"]).await;
+        assert_eq!(list.position_at(area, 1), None);
+        assert_eq!(list.position_at(area, 2), Some(0));
+        assert_eq!(list.position_at(area, 6), Some(4));
+        assert_eq!(list.position_at(area, 7), None);
+
+        let list = seq_list(&["--layout", "reverse"]).await;
+        assert_eq!(list.position_at(area, 5), Some(0));
+        assert_eq!(list.position_at(area, 3), Some(2));
+
+        // Only the last 3 of the 10 results fit when the last one is selected
+        let mut list = seq_list(&[]).await;
+        list.last();
+        list.render(area, &mut Buffer::empty(area));
+        assert_eq!(list.position_at(area, 3), Some(7));
+        assert_eq!(list.position_at(area, 5), Some(9));
+    }
+
+    #[test]
+    fn highlight() {
+        let style = Style::new().bold();
+        assert_eq!(
+            super::highlight("abcd", &[], &[1, 2], 10, style),
+            [Span::from("a"), Span::styled("bc", style), Span::from("d")]
+        );
+        assert_eq!(
+            super::highlight("abcdef", &[], &[0, 5], 4, style),
+            [Span::from("…"), Span::from("de"), Span::styled("f", style)]
+        );
+        assert_eq!(
+            super::highlight("äöü", &[], &[2], 3, style),
+            [Span::from("äö"), Span::styled("ü", style)]
+        );
+        assert_eq!(
+            super::highlight("a\nb\tc\x1b", &[], &[1], 10, style),
+            [
+                Span::from("a"),
+                Span::styled("␊", style),
+                Span::from("b c␛")
+            ]
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use crate::types::mark_order::MarkOrder;
+
+/// Marked items, identified by their index in the searcher rather than their
+/// position in the list, so they stay marked when the query changes.
+#[derive(Default)]
+pub struct Marks {
+    // Maps item indices to the order they were marked in
+    marks: HashMap<u32, usize>,
+    next: usize,
+}
+
+impl Marks {
+    pub fn len(&self) -> usize {
+        self.marks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    pub fn contains(&self, index: u32) -> bool {
+        self.marks.contains_key(&index)
+    }
+
+    pub fn mark(&mut self, index: u32) {
+        if !self.contains(index) {
+            self.marks.insert(index, self.next);
+            self.next += 1;
+        }
+    }
+
+    pub fn unmark(&mut self, index: u32) {
+        self.marks.remove(&index);
+    }
+
+    pub fn toggle(&mut self, index: u32) {
+        if self.contains(index) {
+            self.unmark(index);
+        } else {
+            self.mark(index);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.marks.clear();
+    }
+
+    pub fn indices(&self, order: MarkOrder) -> Vec<u32> {
+        let mut marks: Vec<_> = self.marks.iter().map(|(&i, &o)| (i, o)).collect();
+        match order {
+            MarkOrder::Marked => marks.sort_unstable_by_key(|&(_, order)| order),
+            MarkOrder::Input => marks.sort_unstable_by_key(|&(index, _)| index),
+        }
+        marks.into_iter().map(|(index, _)| index).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indices() {
+        let mut marks = Marks::default();
+        for index in [3, 1, 2, 1, 4] {
+            marks.toggle(index);
+        }
+        marks.mark(4);
+
+        assert_eq!(marks.indices(MarkOrder::Marked), [3, 2, 4]);
+        assert_eq!(marks.indices(MarkOrder::Input), [2, 3, 4]);
+    }
+}
@@ -1,43 +1,262 @@
+use std::{
+    mem,
+    time::{Duration, Instant},
+};
+
 use anyhow::Result;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use input::Input;
 use list::SearchableList;
+use preview::Preview;
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Layout, Rect},
-    widgets::Widget,
+    layout::{Constraint, Layout, Margin, Position, Rect},
+    text::Line,
+    widgets::{Block, Widget},
 };
+use status::{Status, SPINNER_INTERVAL};
 use tokio::sync::watch::Sender;
 
-use crate::{searcher::SearcherSource, types::action::TuiAction};
+use crate::{
+    config::Config,
+    item::Item,
+    template::Context,
+    types::{
+        action::{Action, PreviewAction, TuiAction},
+        list_layout::ListLayout,
+        mark_order::MarkOrder,
+        status_position::StatusPosition,
+    },
+};
 
 mod input;
 mod lazy;
 mod list;
+mod marks;
+mod preview;
+mod status;
+
+/// Time within which a second click on the same item accepts it.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
 
 pub struct Tui<'a> {
     input: Input<'a>,
     list: SearchableList<'a>,
+    preview: Preview,
+    status: Status,
+    layout: ListLayout,
+    borders: bool,
+    mark_order: MarkOrder,
+    delimiter: Option<String>,
+    separator: String,
+    // Areas of the last render, used to find what was clicked
+    list_area: Rect,
+    input_area: Rect,
+    preview_area: Option<Rect>,
+    // Selected item index and query the preview was last updated with
+    preview_input: Option<(Option<u32>, String)>,
+    // Whether marks or the preview changed since the preview was updated
+    preview_stale: bool,
+    // Time and position of the last click on an item
+    last_click: Option<(Instant, usize)>,
 }
 
 impl Tui<'_> {
-    pub fn new(draw_sender: Sender<()>) -> Result<Self> {
-        let input = Input::new();
-        let list = SearchableList::new(SearcherSource::Stdin, draw_sender);
-        Ok(Tui { input, list })
+    pub fn new(config: &Config, draw_sender: Sender<()>) -> Result<Self> {
+        let query = config.query().unwrap_or_default();
+        let input = Input::new(query, config.borders(), config.palette());
+        let delimiter = config.item_format().delimiter().map(str::to_string);
+        let context = Context {
+            query: query.to_string(),
+            delimiter: delimiter.clone(),
+            ..Context::default()
+        };
+        let mut list = SearchableList::new(config, &context, draw_sender.clone());
+        list.search(query);
+        let preview = Preview::new(
+            config.preview().map(str::to_string),
+            config.preview_position(),
+            config.preview_size(),
+            config.palette(),
+            draw_sender,
+        );
+        let status = Status::new(config.status_position(), config.palette().status);
+        Ok(Tui {
+            input,
+            list,
+            preview,
+            status,
+            layout: config.layout(),
+            borders: config.borders(),
+            mark_order: config.mark_order(),
+            delimiter,
+            separator: config.output_separator(),
+            list_area: Rect::default(),
+            input_area: Rect::default(),
+            preview_area: None,
+            preview_input: None,
+            preview_stale: false,
+            last_click: None,
+        })
     }
 
-    pub fn handle_action(&mut self, action: &TuiAction) {
+    /// Handles an action and returns whether it succeeded.
+    pub fn handle_action(&mut self, action: &TuiAction) -> bool {
+        self.preview_stale |= matches!(
+            action,
+            TuiAction::Reload
+                | TuiAction::ToggleMark
+                | TuiAction::MarkAll
+                | TuiAction::UnmarkAll
+                | TuiAction::ToggleAll
+                | TuiAction::Preview(_)
+        );
         match action {
             TuiAction::Next => self.list.next(),
             TuiAction::Previous => self.list.previous(),
             TuiAction::First => self.list.first(),
             TuiAction::Last => self.list.last(),
+            TuiAction::Reload => return self.list.reload(&self.context()),
+            TuiAction::ToggleMark => self.list.toggle_mark(),
+            TuiAction::MarkAll => self.list.mark_all(),
+            TuiAction::UnmarkAll => self.list.unmark_all(),
+            TuiAction::ToggleAll => self.list.toggle_all(),
+            TuiAction::CycleCase => self.list.cycle_case(),
+            TuiAction::CycleAlgorithm => self.list.cycle_algorithm(),
+            TuiAction::ToggleSort => self.list.toggle_sort(),
+            TuiAction::Preview(action) => self.preview.handle_action(action),
             TuiAction::Input(action) => {
                 if let Some(text) = self.input.handle_action(action) {
                     self.list.search(text);
                 }
             }
         }
+        true
+    }
+
+    /// Runs the preview command again if the selected item, the query or the
+    /// marks changed since it was last run.
+    pub fn update_preview(&mut self) {
+        if !self.preview.is_visible() {
+            return;
+        }
+        let selected = self.list.selected();
+        let input = (
+            selected.as_ref().map(|item| item.index),
+            self.input.query().to_string(),
+        );
+        if !mem::take(&mut self.preview_stale) && self.preview_input.as_ref() == Some(&input) {
+            return;
+        }
+
+        // Marks are only collected if they're used, since there can be a lot
+        let accepted = if self.preview.uses_accepted() {
+            self.list.accepted(self.mark_order)
+        } else {
+            Vec::new()
+        };
+        self.preview.update(&Context {
+            selected,
+            accepted,
+            query: input.1.clone(),
+            delimiter: self.delimiter.clone(),
+            separator: self.separator.clone(),
+        });
+        self.preview_input = Some(input);
+    }
+
+    /// Shows an error in the list's border until the next one.
+    pub fn show_error(&self, error: String) {
+        self.list.show_error(error);
+    }
+
+    /// Inserts pasted text into the input and searches once, so pasted text
+    /// can't trigger binds.
+    pub fn paste(&mut self, text: &str) {
+        if let Some(text) = self.input.paste(text) {
+            self.list.search(text);
+        }
+    }
+
+    /// Scrolls the list or preview with the wheel, selects clicked items and
+    /// moves the cursor to clicked text. Returns an action if an item was
+    /// double-clicked.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<Action> {
+        let position = Position::new(mouse.column, mouse.row);
+        let on_preview = self
+            .preview_area
+            .is_some_and(|area| area.contains(position));
+        match mouse.kind {
+            MouseEventKind::ScrollDown if on_preview => {
+                self.preview.handle_action(&PreviewAction::ScrollDown)
+            }
+            MouseEventKind::ScrollUp if on_preview => {
+                self.preview.handle_action(&PreviewAction::ScrollUp)
+            }
+            MouseEventKind::ScrollDown if self.layout.is_reversed() => self.list.previous(),
+            MouseEventKind::ScrollUp if self.layout.is_reversed() => self.list.next(),
+            MouseEventKind::ScrollDown => self.list.next(),
+            MouseEventKind::ScrollUp => self.list.previous(),
+            MouseEventKind::Down(MouseButton::Left) if self.list_area.contains(position) => {
+                let pos = self.list.position_at(self.list_area, mouse.row)?;
+                self.list.select(pos);
+                let now = Instant::now();
+                let double_click = self.last_click.is_some_and(|(time, last_pos)| {
+                    last_pos == pos && now.duration_since(time) < DOUBLE_CLICK_TIME
+                });
+                if double_click {
+                    self.last_click = None;
+                    return Some(Action::Accept);
+                }
+                self.last_click = Some((now, pos));
+            }
+            MouseEventKind::Down(MouseButton::Left) if self.input_area.contains(position) => {
+                if let Some(text) = self.input.jump(self.input_area, mouse.column, mouse.row) {
+                    self.list.search(text);
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+
+    /// Checks if every item has been read and matched.
+    pub fn is_finished(&self) -> bool {
+        self.list.is_finished()
+    }
+
+    /// Waits until every item has been read and matched.
+    pub async fn finish(&mut self) {
+        self.list.finish().await;
+    }
+
+    /// Returns how soon the TUI has to be drawn again without any events,
+    /// which is while items are loading so the spinner keeps spinning.
+    pub fn redraw_interval(&self) -> Option<Duration> {
+        (!self.is_finished()).then_some(SPINNER_INTERVAL)
+    }
+
+    pub fn result_count(&self) -> usize {
+        self.list.result_count()
+    }
+
+    pub fn result(&self, pos: usize) -> Option<Item> {
+        self.list.result(pos)
+    }
+
+    pub fn accepted(&self, order: MarkOrder) -> Vec<Item> {
+        self.list.accepted(order)
+    }
+
+    /// Returns what placeholders in commands are replaced with.
+    pub fn context(&self) -> Context {
+        Context {
+            selected: self.list.selected(),
+            accepted: self.list.accepted(self.mark_order),
+            query: self.input.query().to_string(),
+            delimiter: self.delimiter.clone(),
+            separator: self.separator.clone(),
+        }
     }
 }
 
@@ -46,9 +265,52 @@ impl Widget for &mut Tui<'_> {
     where
         Self: Sized,
     {
-        let [top_area, bottom_area] =
-            Layout::vertical([Constraint::Fill(1), Constraint::Length(5)]).areas(area);
-        self.list.render(top_area, buf);
-        self.input.render(bottom_area, buf);
+        let (main_area, preview_area) = self.preview.split(area);
+        let constraints = [
+            Constraint::Fill(1),
+            Constraint::Length(self.status.height(self.borders)),
+            Constraint::Length(self.input.height()),
+        ];
+        let (list_area, status_area, input_area) = if self.layout.is_prompt_first() {
+            let [input_area, status_area, list_area] =
+                Layout::vertical(constraints.into_iter().rev()).areas(main_area);
+            (list_area, status_area, input_area)
+        } else {
+            let [list_area, status_area, input_area] =
+                Layout::vertical(constraints).areas(main_area);
+            (list_area, status_area, input_area)
+        };
+        self.list.render(list_area, buf);
+        self.input.render(input_area, buf);
+        self.list_area = list_area;
+        self.input_area = input_area;
+        self.preview_area = preview_area;
+
+        let status = (self.status.position() != StatusPosition::Hidden)
+            .then(|| self.status.line(&self.list.counts()));
+        let mut mode = self.list.match_options().to_string();
+        if !self.list.is_sorted() {
+            mode.push_str(" unsorted");
+        }
+        let mut titles = Block::new().title_top(Line::from(mode).right_aligned());
+        if self.borders {
+            match status {
+                Some(status) if self.status.position() == StatusPosition::Line => {
+                    status.render(status_area, buf)
+                }
+                Some(status) => titles = titles.title_top(status),
+                None => (),
+            }
+            titles.render(input_area.inner(Margin::new(1, 0)), buf);
+        } else {
+            if let Some(status) = status {
+                titles = titles.title_top(status);
+            }
+            titles.render(status_area, buf);
+        }
+
+        if let Some(preview_area) = preview_area {
+            self.preview.render(preview_area, buf);
+        }
     }
 }
@@ -0,0 +1,215 @@
+use std::process::Stdio;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Widget},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    join,
+    sync::watch::{self, Receiver, Sender},
+    task::{self, JoinHandle},
+};
+
+use crate::{
+    ansi,
+    template::{Context, Template},
+    types::{
+        action::PreviewAction, extent::Extent, preview_position::PreviewPosition, theme::Palette,
+    },
+    utils::{self, ProcessGroup},
+};
+
+pub struct Preview {
+    command: Option<Template>,
+    position: PreviewPosition,
+    size: Extent,
+    palette: Palette,
+    visible: bool,
+    // Expanded command that was last run, or why it couldn't be expanded
+    expanded: Option<Result<String, String>>,
+    lines: Receiver<Vec<Line<'static>>>,
+    task: Option<JoinHandle<()>>,
+    scroll: usize,
+    height: u16,
+    draw_sender: Sender<()>,
+}
+
+impl Preview {
+    pub fn new(
+        command: Option<String>,
+        position: PreviewPosition,
+        size: Extent,
+        palette: Palette,
+        draw_sender: Sender<()>,
+    ) -> Self {
+        Preview {
+            command: command.as_deref().map(Template::parse),
+            position,
+            size,
+            palette,
+            visible: true,
+            expanded: None,
+            lines: watch::channel(Vec::new()).1,
+            task: None,
+            scroll: 0,
+            height: 0,
+            draw_sender,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible && self.command.is_some()
+    }
+
+    /// Checks if the command is run with every accepted item, rather than
+    /// just the selected one.
+    pub fn uses_accepted(&self) -> bool {
+        self.command.as_ref().is_some_and(Template::uses_accepted)
+    }
+
+    pub fn handle_action(&mut self, action: &PreviewAction) {
+        match action {
+            PreviewAction::Toggle => self.visible = !self.visible,
+            PreviewAction::ScrollUp => self.scroll = self.scroll.saturating_sub(1),
+            PreviewAction::ScrollDown => self.scroll += 1,
+            PreviewAction::PageUp => self.scroll = self.scroll.saturating_sub(self.height.into()),
+            PreviewAction::PageDown => self.scroll += self.height as usize,
+        }
+    }
+
+    /// Splits an area into the area left for the rest of the TUI and the area
+    /// of the preview, if it's visible.
+    pub fn split(&self, area: Rect) -> (Rect, Option<Rect>) {
+        if !self.is_visible() {
+            return (area, None);
+        }
+
+        let size = if self.position.is_horizontal() {
+            self.size.cells(area.width)
+        } else {
+            self.size.cells(area.height)
+        };
+        let mut constraints = [Constraint::Fill(1), Constraint::Length(size)];
+        if self.position.is_first() {
+            constraints.reverse();
+        }
+        let [mut main_area, mut preview_area] = if self.position.is_horizontal() {
+            Layout::horizontal(constraints).areas(area)
+        } else {
+            Layout::vertical(constraints).areas(area)
+        };
+        if self.position.is_first() {
+            (main_area, preview_area) = (preview_area, main_area);
+        }
+        (main_area, Some(preview_area))
+    }
+
+    /// Runs the command for the selected item, unless it expands to the
+    /// command that was last run. The output of the previous command is
+    /// discarded.
+    pub fn update(&mut self, context: &Context) {
+        if !self.is_visible() {
+            return;
+        }
+        let expanded = match (&self.command, &context.selected) {
+            (Some(command), Some(_)) => Some(command.expand(context).map_err(|e| e.to_string())),
+            _ => None,
+        };
+        if self.expanded == expanded {
+            return;
+        }
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+
+        let (sender, receiver) = watch::channel(Vec::new());
+        self.lines = receiver;
+        self.expanded = expanded.clone();
+        self.scroll = 0;
+        match expanded {
+            Some(Ok(command)) => {
+                self.task = Some(task::spawn(Preview::run(
+                    command,
+                    sender,
+                    self.draw_sender.clone(),
+                )));
+            }
+            Some(Err(error)) => sender.send_modify(|lines| lines.push(error.into())),
+            None => (),
+        }
+    }
+
+    async fn run(command: String, lines: Sender<Vec<Line<'static>>>, draw_sender: Sender<()>) {
+        let mut child = match utils::shell_command(&command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(error) => {
+                lines.send_modify(|lines| lines.push(error.to_string().into()));
+                let _ = draw_sender.send(());
+                return;
+            }
+        };
+        let mut process_group = ProcessGroup::new(child.id());
+
+        let stdout = child.stdout.take().expect("stdout should be piped");
+        let stderr = child.stderr.take().expect("stderr should be piped");
+        join!(
+            Preview::read_lines(stdout, &lines, &draw_sender),
+            Preview::read_lines(stderr, &lines, &draw_sender)
+        );
+
+        let _ = child.wait().await;
+        process_group.release();
+    }
+
+    async fn read_lines<R>(reader: R, lines: &Sender<Vec<Line<'static>>>, draw_sender: &Sender<()>)
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        while reader
+            .read_until(b'\n', &mut buf)
+            .await
+            .is_ok_and(|len| len > 0)
+        {
+            let line = ansi::parse_line(&String::from_utf8_lossy(&buf));
+            lines.send_modify(|lines| lines.push(line));
+            let _ = draw_sender.send(());
+            buf.clear();
+        }
+    }
+}
+
+impl Drop for Preview {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
+
+impl Widget for &mut Preview {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.height = area.height.saturating_sub(2);
+        let lines = self.lines.borrow();
+        self.scroll = self.scroll.min(lines.len().saturating_sub(1));
+        let text: Text = lines
+            .iter()
+            .skip(self.scroll)
+            .take(self.height.into())
+            .cloned()
+            .collect();
+        Paragraph::new(text)
+            .style(self.palette.preview)
+            .block(Block::bordered().border_style(self.palette.border))
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::{Duration, Instant};
+
+use ratatui::{style::Style, text::Line};
+
+use crate::types::status_position::StatusPosition;
+
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+/// Time each frame of the spinner is shown.
+pub const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Numbers shown in the status line.
+pub struct Counts {
+    pub matched: usize,
+    pub total: usize,
+    pub marked: usize,
+    /// Whether items are still being read or matched
+    pub loading: bool,
+}
+
+pub struct Status {
+    position: StatusPosition,
+    style: Style,
+    start: Instant,
+}
+
+impl Status {
+    pub fn new(position: StatusPosition, style: Style) -> Self {
+        Status {
+            position,
+            style,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn position(&self) -> StatusPosition {
+        self.position
+    }
+
+    /// Returns the height of the status line if it's shown on its own line.
+    /// Without borders, there's always a line for it and the match mode.
+    pub fn height(&self, borders: bool) -> u16 {
+        match (self.position, borders) {
+            (StatusPosition::Line, _) | (_, false) => 1,
+            (StatusPosition::Border | StatusPosition::Hidden, true) => 0,
+        }
+    }
+
+    pub fn line(&self, counts: &Counts) -> Line<'static> {
+        let spinner = if counts.loading {
+            let frame = self.start.elapsed().as_millis() / SPINNER_INTERVAL.as_millis();
+            SPINNER[frame as usize % SPINNER.len()]
+        } else {
+            " "
+        };
+        let mut line = format!("{spinner} {}/{}", counts.matched, counts.total);
+        if counts.marked > 0 {
+            line.push_str(&format!(" ({} marked)", counts.marked));
+        }
+        Line::styled(line, self.style)
+    }
+}
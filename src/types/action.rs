@@ -10,12 +10,27 @@ use serde::{
 };
 use thiserror::Error;
 
+use crate::template::Template;
+
 use super::key::Key;
 
 #[derive(Clone, Debug, From, PartialEq)]
 pub enum Action {
     Exit,
+    Accept,
     Draw,
+    /// Run a command in the terminal while the TUI is suspended
+    #[from(skip)]
+    Execute(Template),
+    /// Run a command in the background
+    #[from(skip)]
+    ExecuteSilent(Template),
+    /// Replace tez with a command
+    #[from(skip)]
+    Become(Template),
+    /// Run actions in order, until one fails
+    #[from(skip)]
+    Chain(Vec<Action>),
     Tui(TuiAction),
 }
 
@@ -25,9 +40,27 @@ pub enum TuiAction {
     Previous,
     First,
     Last,
+    Reload,
+    ToggleMark,
+    MarkAll,
+    UnmarkAll,
+    ToggleAll,
+    CycleCase,
+    CycleAlgorithm,
+    ToggleSort,
+    Preview(PreviewAction),
     Input(InputAction),
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum PreviewAction {
+    Toggle,
+    ScrollUp,
+    ScrollDown,
+    PageUp,
+    PageDown,
+}
+
 #[derive(Clone, Debug, From, PartialEq)]
 pub enum InputAction {
     Key(Key),
@@ -42,6 +75,7 @@ pub enum InputAction {
     MoveToBottom,
     MoveToHead,
     MoveToEnd,
+    Clear,
     Delete,
     DeleteNext,
     DeleteWord,
@@ -52,12 +86,46 @@ pub enum InputAction {
 
 impl Action {
     fn parse(s: &str) -> Result<Self, ParseActionError> {
+        let steps = split_chain(s);
+        if steps.len() > 1 {
+            return steps
+                .into_iter()
+                .map(|step| Action::parse_step(step.trim()))
+                .collect::<Result<_, _>>()
+                .map(Action::Chain);
+        }
+        Action::parse_step(s)
+    }
+
+    fn parse_step(s: &str) -> Result<Self, ParseActionError> {
+        if let Some((name, arg)) = split_parameter(s) {
+            return Ok(match name {
+                "execute" => Action::Execute(Template::parse(arg)),
+                "execute-silent" => Action::ExecuteSilent(Template::parse(arg)),
+                "become" => Action::Become(Template::parse(arg)),
+                _ => Err(ParseActionError(s.to_string()))?,
+            });
+        }
         Ok(match s {
             "exit" => Action::Exit,
+            "accept" => Action::Accept,
             "next" => TuiAction::Next.into(),
             "previous" => TuiAction::Previous.into(),
             "first" => TuiAction::First.into(),
             "last" => TuiAction::Last.into(),
+            "reload" => TuiAction::Reload.into(),
+            "toggle-mark" => TuiAction::ToggleMark.into(),
+            "mark-all" => TuiAction::MarkAll.into(),
+            "unmark-all" => TuiAction::UnmarkAll.into(),
+            "toggle-all" => TuiAction::ToggleAll.into(),
+            "cycle-case" => TuiAction::CycleCase.into(),
+            "cycle-algorithm" => TuiAction::CycleAlgorithm.into(),
+            "toggle-sort" => TuiAction::ToggleSort.into(),
+            "toggle-preview" => PreviewAction::Toggle.into(),
+            "preview-up" => PreviewAction::ScrollUp.into(),
+            "preview-down" => PreviewAction::ScrollDown.into(),
+            "preview-page-up" => PreviewAction::PageUp.into(),
+            "preview-page-down" => PreviewAction::PageDown.into(),
             "move-forward" => InputAction::MoveForward.into(),
             "move-back" => InputAction::MoveBack.into(),
             "move-up" => InputAction::MoveUp.into(),
@@ -69,6 +137,7 @@ impl Action {
             "move-to-bottom" => InputAction::MoveToBottom.into(),
             "move-to-head" => InputAction::MoveToHead.into(),
             "move-to-end" => InputAction::MoveToEnd.into(),
+            "clear-query" => InputAction::Clear.into(),
             "delete" => InputAction::Delete.into(),
             "delete-next" => InputAction::DeleteNext.into(),
             "delete-word" => InputAction::DeleteWord.into(),
@@ -80,6 +149,38 @@ impl Action {
     }
 }
 
+/// Splits actions chained with `+`, ignoring any `+` within parentheses.
+fn split_chain(s: &str) -> Vec<&str> {
+    let mut steps = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '+' if depth == 0 => {
+                steps.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    steps.push(&s[start..]);
+    steps
+}
+
+/// Splits an action like `name(parameter)` into its name and parameter.
+fn split_parameter(s: &str) -> Option<(&str, &str)> {
+    let (name, rest) = s.split_once('(')?;
+    Some((name, rest.strip_suffix(')')?))
+}
+
+impl From<PreviewAction> for Action {
+    fn from(value: PreviewAction) -> Self {
+        Action::Tui(TuiAction::Preview(value))
+    }
+}
+
 impl From<InputAction> for Action {
     fn from(value: InputAction) -> Self {
         Action::Tui(TuiAction::Input(value))
@@ -130,9 +231,35 @@ mod tests {
 
     #[test]
     fn deserialize() {
-        let strings = ["exit", "invalid"];
+        let strings = [
+            "exit",
+            "accept",
+            "execute(less {})",
+            "execute-silent(echo (a))",
+            "become(vi {+})",
+            "clear-query + reload+first",
+            "execute(a+b)+accept",
+            "next+invalid",
+            "invalid",
+        ];
         let parsed_strings = [
             Ok(Action::Exit),
+            Ok(Action::Accept),
+            Ok(Action::Execute(Template::parse("less {}"))),
+            Ok(Action::ExecuteSilent(Template::parse("echo (a)"))),
+            Ok(Action::Become(Template::parse("vi {+}"))),
+            Ok(Action::Chain(vec![
+                InputAction::Clear.into(),
+                TuiAction::Reload.into(),
+                TuiAction::First.into(),
+            ])),
+            Ok(Action::Chain(vec![
+                Action::Execute(Template::parse("a+b")),
+                Action::Accept,
+            ])),
+            Err(<toml::de::Error as de::Error>::custom(ParseActionError(
+                "invalid".to_string(),
+            ))),
             Err(<toml::de::Error as de::Error>::custom(ParseActionError(
                 "invalid".to_string(),
             ))),
@@ -17,6 +17,7 @@ pub struct Bind {
 }
 
 impl Bind {
+    #[cfg(test)]
     pub fn new(key: Key, action: Action) -> Self {
         Bind { key, action }
     }
@@ -56,6 +57,7 @@ impl FromStr for Bind {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::action::TuiAction;
     use crossterm::event::{KeyCode as K, KeyModifiers as M};
 
     #[test]
@@ -69,6 +71,7 @@ mod tests {
             "alt+alt+::exit",
             "::invalid",
             ": : invalid",
+            "ctrl+r:reload+first",
         ];
         let parsed_strings = [
             Ok(Bind::new(Key::new(K::Char(':'), M::NONE), Action::Exit)),
@@ -79,6 +82,10 @@ mod tests {
             Err(ParseKeyError::DuplicateModifier("alt".to_string()).into()),
             Err(ParseActionError("invalid".to_string()).into()),
             Err(ParseActionError("invalid".to_string()).into()),
+            Ok(Bind::new(
+                Key::new(K::Char('r'), M::CONTROL),
+                Action::Chain(vec![TuiAction::Reload.into(), TuiAction::First.into()]),
+            )),
         ];
 
         assert_eq!(strings.map(|s| s.parse()), parsed_strings);
@@ -0,0 +1,101 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use derive_more::From;
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+
+use super::fields::{Fields, ParseFieldsError};
+
+/// A named group of fields that can be searched separately with `name:query`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub fields: Fields,
+}
+
+impl Column {
+    fn parse(s: &str) -> Result<Self, ParseColumnError> {
+        let (name, fields) = s.split_once('=').ok_or(ParseColumnError::InvalidFormat)?;
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            Err(ParseColumnError::InvalidName(name.to_string()))?;
+        }
+        Ok(Column {
+            name: name.to_string(),
+            fields: fields.parse()?,
+        })
+    }
+
+    /// Deserializes columns from a table of names and fields.
+    pub fn deserialize_table<'de, D>(deserializer: D) -> Result<Vec<Column>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(BTreeMap::<String, Fields>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(name, fields)| Column { name, fields })
+            .collect())
+    }
+}
+
+#[derive(Debug, Error, From, PartialEq)]
+#[error("failed to parse column")]
+pub enum ParseColumnError {
+    #[error("invalid format")]
+    InvalidFormat,
+    #[error("invalid name: {_0}")]
+    InvalidName(String),
+    #[error("{_0}")]
+    ParseFieldsError(ParseFieldsError),
+}
+
+impl FromStr for Column {
+    type Err = ParseColumnError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Column::parse(s.trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, fields: &str) -> Column {
+        Column {
+            name: name.to_string(),
+            fields: fields.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let strings = ["name=1", " ext = 2.. ", "name", "=1", "a b=1", "name=0"];
+        let parsed_strings = [
+            Ok(column("name", "1")),
+            Ok(column("ext", "2..")),
+            Err(ParseColumnError::InvalidFormat),
+            Err(ParseColumnError::InvalidName("".to_string())),
+            Err(ParseColumnError::InvalidName("a b".to_string())),
+            Err(ParseFieldsError("0".to_string()).into()),
+        ];
+
+        assert_eq!(strings.map(|s| s.parse()), parsed_strings);
+    }
+
+    #[test]
+    fn deserialize() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Table {
+            #[serde(deserialize_with = "Column::deserialize_table")]
+            columns: Vec<Column>,
+        }
+
+        assert_eq!(
+            toml::from_str("columns = { name = 1, ext = '2..' }"),
+            Ok(Table {
+                columns: vec![column("ext", "2.."), column("name", "1")]
+            })
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::Range,
+    str::FromStr,
+};
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer,
+};
+use thiserror::Error;
+
+/// An inclusive range of fields, where negative indices count from the end.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FieldRange {
+    start: Option<isize>,
+    end: Option<isize>,
+}
+
+impl FieldRange {
+    fn parse(s: &str) -> Result<Self, ParseFieldsError> {
+        let (start, end) = match s.split_once("..") {
+            Some((start, end)) => (
+                FieldRange::parse_index(start.trim(), true)?,
+                FieldRange::parse_index(end.trim(), true)?,
+            ),
+            None => {
+                let index = FieldRange::parse_index(s, false)?;
+                (index, index)
+            }
+        };
+        Ok(FieldRange { start, end })
+    }
+
+    fn parse_index(s: &str, optional: bool) -> Result<Option<isize>, ParseFieldsError> {
+        match s.parse() {
+            Ok(0) => Err(ParseFieldsError(s.to_string())),
+            Ok(index) => Ok(Some(index)),
+            Err(_) if s.is_empty() && optional => Ok(None),
+            Err(_) => Err(ParseFieldsError(s.to_string())),
+        }
+    }
+
+    /// Converts the range to a range of indices into `count` fields.
+    pub fn indices(&self, count: usize) -> Range<usize> {
+        let start = match self.start {
+            None => 0,
+            Some(i) if i > 0 => i as usize - 1,
+            Some(i) => count.saturating_sub(i.unsigned_abs()),
+        };
+        let end = match self.end {
+            None => count,
+            Some(i) if i > 0 => (i as usize).min(count),
+            Some(i) => (count + 1).saturating_sub(i.unsigned_abs()),
+        };
+        start.min(end)..end
+    }
+}
+
+/// A comma-separated list of field ranges, e.g. `1,3..5,-1`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fields(Vec<FieldRange>);
+
+impl Fields {
+    fn parse(s: &str) -> Result<Self, ParseFieldsError> {
+        s.split(',')
+            .map(|range| FieldRange::parse(range.trim()))
+            .collect::<Result<_, _>>()
+            .map(Fields)
+    }
+
+    /// Returns the indices of the selected fields out of `count` fields.
+    pub fn indices(&self, count: usize) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().flat_map(move |range| range.indices(count))
+    }
+
+    /// Selects fields from a string, removing the delimiter after the last one.
+    pub fn select(&self, s: &str, delimiter: Option<&str>) -> String {
+        let fields = split(s, delimiter);
+        let mut selected: String = self
+            .indices(fields.len())
+            .map(|i| &s[fields[i].clone()])
+            .collect();
+        match delimiter.filter(|delimiter| !delimiter.is_empty()) {
+            Some(delimiter) => {
+                if selected.ends_with(delimiter) {
+                    selected.truncate(selected.len() - delimiter.len());
+                }
+            }
+            None => selected.truncate(selected.trim_end().len()),
+        }
+        selected
+    }
+}
+
+/// Splits a string into the byte ranges of its fields. Every field includes
+/// the delimiter that follows it. Without a delimiter, fields are separated by
+/// whitespace and leading whitespace belongs to the first field.
+pub fn split(s: &str, delimiter: Option<&str>) -> Vec<Range<usize>> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    match delimiter.filter(|delimiter| !delimiter.is_empty()) {
+        Some(delimiter) => {
+            for (i, _) in s.match_indices(delimiter) {
+                fields.push(start..i + delimiter.len());
+                start = i + delimiter.len();
+            }
+        }
+        None => {
+            let mut in_whitespace = false;
+            let mut seen_field = false;
+            for (i, c) in s.char_indices() {
+                if c.is_whitespace() {
+                    in_whitespace = seen_field;
+                } else {
+                    if in_whitespace {
+                        fields.push(start..i);
+                        start = i;
+                    }
+                    in_whitespace = false;
+                    seen_field = true;
+                }
+            }
+        }
+    }
+    if start < s.len() {
+        fields.push(start..s.len());
+    }
+    fields
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("invalid field index: {_0}")]
+pub struct ParseFieldsError(pub String);
+
+impl FromStr for Fields {
+    type Err = ParseFieldsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Fields::parse(s.trim())
+    }
+}
+
+impl<'de> Deserialize<'de> for Fields {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(FieldsVisitor)
+    }
+}
+
+struct FieldsVisitor;
+
+impl Visitor<'_> for FieldsVisitor {
+    type Value = Fields;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        "a field index or a list of field ranges (e.g. 1,3..5,-1)".fmt(formatter)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.to_string().parse().map_err(de::Error::custom)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: Option<isize>, end: Option<isize>) -> FieldRange {
+        FieldRange { start, end }
+    }
+
+    #[test]
+    fn parse() {
+        let strings = [
+            "1",
+            "-1",
+            "2..",
+            "..3",
+            "1..-2",
+            "..",
+            " 1 , 3 .. 4 ",
+            "0",
+            "a",
+            "1..",
+        ];
+        let parsed_strings = [
+            Ok(Fields(vec![range(Some(1), Some(1))])),
+            Ok(Fields(vec![range(Some(-1), Some(-1))])),
+            Ok(Fields(vec![range(Some(2), None)])),
+            Ok(Fields(vec![range(None, Some(3))])),
+            Ok(Fields(vec![range(Some(1), Some(-2))])),
+            Ok(Fields(vec![range(None, None)])),
+            Ok(Fields(vec![
+                range(Some(1), Some(1)),
+                range(Some(3), Some(4)),
+            ])),
+            Err(ParseFieldsError("0".to_string())),
+            Err(ParseFieldsError("a".to_string())),
+            Ok(Fields(vec![range(Some(1), None)])),
+        ];
+
+        assert_eq!(strings.map(|s| s.parse()), parsed_strings);
+    }
+
+    #[test]
+    fn deserialize() {
+        assert_eq!(
+            toml::Value::Integer(2).try_into(),
+            Ok(Fields(vec![range(Some(2), Some(2))]))
+        );
+        assert_eq!(
+            toml::Value::String("0".to_string()).try_into::<Fields>(),
+            Err(<toml::de::Error as de::Error>::custom(ParseFieldsError(
+                "0".to_string()
+            )))
+        );
+    }
+
+    #[test]
+    fn indices() {
+        assert_eq!(range(Some(2), Some(3)).indices(5), 1..3);
+        assert_eq!(range(Some(-2), None).indices(5), 3..5);
+        assert_eq!(range(None, Some(-2)).indices(5), 0..4);
+        assert_eq!(range(Some(4), Some(9)).indices(2), 2..2);
+        assert_eq!(range(Some(-9), Some(-7)).indices(5), 0..0);
+    }
+
+    #[test]
+    fn split() {
+        assert_eq!(super::split("  a b\tc ", None), [0..4, 4..6, 6..8]);
+        assert_eq!(super::split("a:b::c", Some(":")), [0..2, 2..4, 4..5, 5..6]);
+        assert_eq!(super::split("a:b", Some(":")), [0..2, 2..3]);
+        assert_eq!(super::split("", None), [] as [Range<usize>; 0]);
+    }
+
+    #[test]
+    fn select() {
+        let fields: Fields = "2..".parse().unwrap();
+        assert_eq!(fields.select("a b  c", None), "b  c");
+        let fields: Fields = "-1,1".parse().unwrap();
+        assert_eq!(fields.select("a:b:c", Some(":")), "ca");
+    }
+}
@@ -0,0 +1,47 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// Arrangement of the list and the input.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ListLayout {
+    /// Input below the list, with the best match at the top
+    #[default]
+    Default,
+    /// Input below the list, with the best match at the bottom
+    Reverse,
+    /// Input above the list, with the best match at the top
+    TopPrompt,
+}
+
+impl ListLayout {
+    pub fn is_prompt_first(&self) -> bool {
+        matches!(self, ListLayout::TopPrompt)
+    }
+
+    /// Checks if the list is drawn from the bottom up.
+    pub fn is_reversed(&self) -> bool {
+        matches!(self, ListLayout::Reverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let strings = ["default", "reverse", "top-prompt", "invalid"];
+        let parsed_strings = strings.map(|s| toml::Value::String(s.to_string()).try_into().ok());
+
+        assert_eq!(
+            parsed_strings,
+            [
+                Some(ListLayout::Default),
+                Some(ListLayout::Reverse),
+                Some(ListLayout::TopPrompt),
+                None
+            ]
+        );
+    }
+}
@@ -0,0 +1,29 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// Order in which marked items are accepted.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum MarkOrder {
+    /// The order the items were marked in
+    #[default]
+    Marked,
+    /// The order the items were read in
+    Input,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let strings = ["marked", "input", "invalid"];
+        let parsed_strings = strings.map(|s| toml::Value::String(s.to_string()).try_into().ok());
+
+        assert_eq!(
+            parsed_strings,
+            [Some(MarkOrder::Marked), Some(MarkOrder::Input), None]
+        );
+    }
+}
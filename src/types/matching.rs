@@ -0,0 +1,183 @@
+use std::fmt::{self, Display, Formatter};
+
+use clap::ValueEnum;
+use nucleo::pattern::{CaseMatching, Normalization};
+use serde::Deserialize;
+
+use crate::utils;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum CaseMode {
+    /// Ignore case unless the query contains uppercase characters
+    #[default]
+    Smart,
+    Ignore,
+    Respect,
+}
+
+impl CaseMode {
+    pub fn next(self) -> Self {
+        match self {
+            CaseMode::Smart => CaseMode::Ignore,
+            CaseMode::Ignore => CaseMode::Respect,
+            CaseMode::Respect => CaseMode::Smart,
+        }
+    }
+}
+
+impl From<CaseMode> for CaseMatching {
+    fn from(value: CaseMode) -> Self {
+        match value {
+            CaseMode::Smart => CaseMatching::Smart,
+            CaseMode::Ignore => CaseMatching::Ignore,
+            CaseMode::Respect => CaseMatching::Respect,
+        }
+    }
+}
+
+impl Display for CaseMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CaseMode::Smart => "smart-case".fmt(f),
+            CaseMode::Ignore => "ignore-case".fmt(f),
+            CaseMode::Respect => "respect-case".fmt(f),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum NormalizationMode {
+    /// Ignore diacritics unless the query contains them
+    #[default]
+    Smart,
+    Never,
+}
+
+impl From<NormalizationMode> for Normalization {
+    fn from(value: NormalizationMode) -> Self {
+        match value {
+            NormalizationMode::Smart => Normalization::Smart,
+            NormalizationMode::Never => Normalization::Never,
+        }
+    }
+}
+
+/// How query words without explicit operators are matched.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Algorithm {
+    /// Match characters in order, with gaps
+    #[default]
+    Fuzzy,
+    /// Match the entire text
+    Exact,
+    /// Match the start of the text
+    Prefix,
+    /// Match a contiguous part of the text
+    Substring,
+}
+
+impl Algorithm {
+    pub fn next(self) -> Self {
+        match self {
+            Algorithm::Fuzzy => Algorithm::Substring,
+            Algorithm::Substring => Algorithm::Prefix,
+            Algorithm::Prefix => Algorithm::Exact,
+            Algorithm::Exact => Algorithm::Fuzzy,
+        }
+    }
+
+    /// Adds operators to every word of a pattern that doesn't have any, so
+    /// they're matched with this algorithm.
+    pub fn apply(self, pattern: &str) -> String {
+        if self == Algorithm::Fuzzy {
+            return pattern.to_string();
+        }
+
+        let words: Vec<_> = utils::split_words(pattern)
+            .map(|word| {
+                let (negation, atom) = match word.strip_prefix('!') {
+                    Some(atom) => ("!", atom),
+                    None => ("", word),
+                };
+                let explicit = atom.starts_with(['^', '\'', '\\'])
+                    || (atom.ends_with('$') && !atom.ends_with("\\$"));
+                match self {
+                    _ if explicit || atom.is_empty() => word.to_string(),
+                    Algorithm::Fuzzy => word.to_string(),
+                    Algorithm::Exact => format!("{negation}^{atom}$"),
+                    Algorithm::Prefix => format!("{negation}^{atom}"),
+                    // Negated words are always matched as substrings
+                    Algorithm::Substring if !negation.is_empty() => word.to_string(),
+                    Algorithm::Substring => format!("'{atom}"),
+                }
+            })
+            .collect();
+        words.join(" ")
+    }
+}
+
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Algorithm::Fuzzy => "fuzzy".fmt(f),
+            Algorithm::Exact => "exact".fmt(f),
+            Algorithm::Prefix => "prefix".fmt(f),
+            Algorithm::Substring => "substring".fmt(f),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MatchOptions {
+    pub case: CaseMode,
+    pub normalization: NormalizationMode,
+    pub algorithm: Algorithm,
+}
+
+impl Display for MatchOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.algorithm, self.case)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        let pattern = "foo !bar ^baz qux$ a\\$";
+        assert_eq!(Algorithm::Fuzzy.apply(pattern), pattern);
+        assert_eq!(
+            Algorithm::Substring.apply(pattern),
+            "'foo !bar ^baz qux$ 'a\\$"
+        );
+        assert_eq!(
+            Algorithm::Prefix.apply(pattern),
+            "^foo !^bar ^baz qux$ ^a\\$"
+        );
+        assert_eq!(
+            Algorithm::Exact.apply(pattern),
+            "^foo$ !^bar$ ^baz qux$ ^a\\$$"
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        assert_eq!(
+            toml::Value::String("ignore".to_string()).try_into(),
+            Ok(CaseMode::Ignore)
+        );
+        assert_eq!(
+            toml::Value::String("never".to_string()).try_into(),
+            Ok(NormalizationMode::Never)
+        );
+        assert_eq!(
+            toml::Value::String("substring".to_string()).try_into(),
+            Ok(Algorithm::Substring)
+        );
+    }
+}
@@ -1,5 +1,15 @@
 pub mod action;
 pub mod alignment;
 pub mod bind;
+pub mod column;
 pub mod extent;
+pub mod fields;
 pub mod key;
+pub mod list_layout;
+pub mod mark_order;
+pub mod matching;
+pub mod preview_position;
+pub mod sorting;
+pub mod status_position;
+pub mod style;
+pub mod theme;
@@ -0,0 +1,24 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// Side of the list the preview is shown on.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum PreviewPosition {
+    Top,
+    Bottom,
+    Left,
+    #[default]
+    Right,
+}
+
+impl PreviewPosition {
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, PreviewPosition::Left | PreviewPosition::Right)
+    }
+
+    /// Checks if the preview comes before the list.
+    pub fn is_first(&self) -> bool {
+        matches!(self, PreviewPosition::Top | PreviewPosition::Left)
+    }
+}
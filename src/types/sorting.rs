@@ -0,0 +1,72 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// Criterion that orders results with the same score.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Tiebreak {
+    /// Prefer shorter items
+    Length,
+    /// Prefer items where the match starts earlier
+    Begin,
+    /// Prefer items where the match ends closer to the end
+    End,
+    /// Prefer items that were read first
+    Index,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SortOptions {
+    /// Sort results by score, otherwise they're kept in input order
+    pub sort: bool,
+    pub tiebreaks: Vec<Tiebreak>,
+}
+
+impl SortOptions {
+    pub const DEFAULT_TIEBREAKS: [Tiebreak; 2] = [Tiebreak::Length, Tiebreak::Index];
+
+    /// Checks if results are already in the order nucleo sorts them in.
+    pub fn is_default(&self) -> bool {
+        self.sort && self.tiebreaks == SortOptions::DEFAULT_TIEBREAKS
+    }
+
+    /// Checks if match positions are needed to break ties.
+    pub fn needs_indices(&self) -> bool {
+        self.sort
+            && self
+                .tiebreaks
+                .iter()
+                .any(|tiebreak| matches!(tiebreak, Tiebreak::Begin | Tiebreak::End))
+    }
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        SortOptions {
+            sort: true,
+            tiebreaks: SortOptions::DEFAULT_TIEBREAKS.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let strings = ["length", "begin", "end", "index", "invalid"];
+        let parsed_strings = strings.map(|s| toml::Value::String(s.to_string()).try_into().ok());
+
+        assert_eq!(
+            parsed_strings,
+            [
+                Some(Tiebreak::Length),
+                Some(Tiebreak::Begin),
+                Some(Tiebreak::End),
+                Some(Tiebreak::Index),
+                None
+            ]
+        );
+    }
+}
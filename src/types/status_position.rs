@@ -0,0 +1,35 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// Where the status line is shown.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusPosition {
+    /// In the border of the input
+    #[default]
+    Border,
+    /// On its own line above the input
+    Line,
+    Hidden,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let strings = ["border", "line", "hidden", "invalid"];
+        let parsed_strings = strings.map(|s| toml::Value::String(s.to_string()).try_into().ok());
+
+        assert_eq!(
+            parsed_strings,
+            [
+                Some(StatusPosition::Border),
+                Some(StatusPosition::Line),
+                Some(StatusPosition::Hidden),
+                None
+            ]
+        );
+    }
+}
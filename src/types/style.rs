@@ -0,0 +1,149 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use ratatui::style::{self, Color, Modifier};
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer,
+};
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Style(style::Style);
+
+impl Style {
+    fn parse(s: &str) -> Result<Self, ParseStyleError> {
+        let mut style = style::Style::new();
+        for word in s.split_whitespace() {
+            style = if let Some(color) = word.strip_prefix("fg:") {
+                style.fg(Style::parse_color(color)?)
+            } else if let Some(color) = word.strip_prefix("bg:") {
+                style.bg(Style::parse_color(color)?)
+            } else if let Some(modifier) = Style::parse_modifier(word) {
+                style.add_modifier(modifier)
+            } else {
+                style.fg(Style::parse_color(word)?)
+            };
+        }
+        Ok(Style(style))
+    }
+
+    fn parse_color(s: &str) -> Result<Color, ParseStyleError> {
+        s.parse()
+            .map_err(|_| ParseStyleError::InvalidColor(s.to_string()))
+    }
+
+    fn parse_modifier(s: &str) -> Option<Modifier> {
+        Some(match s {
+            "bold" => Modifier::BOLD,
+            "dim" => Modifier::DIM,
+            "italic" => Modifier::ITALIC,
+            "underlined" => Modifier::UNDERLINED,
+            "slow-blink" => Modifier::SLOW_BLINK,
+            "rapid-blink" => Modifier::RAPID_BLINK,
+            "reversed" => Modifier::REVERSED,
+            "hidden" => Modifier::HIDDEN,
+            "crossed-out" => Modifier::CROSSED_OUT,
+            _ => None?,
+        })
+    }
+}
+
+impl From<Style> for style::Style {
+    fn from(value: Style) -> Self {
+        value.0
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("failed to parse style")]
+pub enum ParseStyleError {
+    #[error("invalid color: {_0}")]
+    InvalidColor(String),
+}
+
+impl FromStr for Style {
+    type Err = ParseStyleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Style::parse(s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Style {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(StyleVisitor)
+    }
+}
+
+struct StyleVisitor;
+
+impl Visitor<'_> for StyleVisitor {
+    type Value = Style;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        "a style made of colors (<COLOR> | fg:<COLOR> | bg:<COLOR>) and modifiers".fmt(formatter)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Stylize;
+
+    #[test]
+    fn parse() {
+        let strings = [
+            "",
+            "red",
+            "fg:red bg:#000000",
+            " bold  light-blue ",
+            "bold italic bg:7",
+            "invalid",
+            "bg:invalid",
+        ];
+        let parsed_strings = [
+            Ok(Style::default()),
+            Ok(Style(style::Style::new().fg(Color::Red))),
+            Ok(Style(
+                style::Style::new().fg(Color::Red).bg(Color::Rgb(0, 0, 0)),
+            )),
+            Ok(Style(style::Style::new().bold().fg(Color::LightBlue))),
+            Ok(Style(
+                style::Style::new().bold().italic().bg(Color::Indexed(7)),
+            )),
+            Err(ParseStyleError::InvalidColor("invalid".to_string())),
+            Err(ParseStyleError::InvalidColor("invalid".to_string())),
+        ];
+
+        assert_eq!(strings.map(|s| s.parse()), parsed_strings);
+    }
+
+    #[test]
+    fn deserialize() {
+        let strings = ["red", "invalid"];
+        let parsed_strings = [
+            Ok(Style(style::Style::new().fg(Color::Red))),
+            Err(<toml::de::Error as de::Error>::custom(
+                ParseStyleError::InvalidColor("invalid".to_string()),
+            )),
+        ];
+
+        assert_eq!(
+            strings.map(|s| toml::Value::String(s.to_string()).try_into()),
+            parsed_strings
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use clap::ValueEnum;
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+
+/// Built-in theme that styles which aren't set are taken from.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    /// For terminals with a dark background
+    #[default]
+    Dark,
+    /// For terminals with a light background
+    Light,
+}
+
+impl ThemePreset {
+    pub fn palette(&self) -> Palette {
+        match self {
+            ThemePreset::Dark => Palette::DARK,
+            ThemePreset::Light => Palette::LIGHT,
+        }
+    }
+}
+
+/// Styles of the parts of the TUI.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
+    pub selected: Style,
+    pub matched: Style,
+    pub marker: Style,
+    pub border: Style,
+    pub prompt: Style,
+    pub status: Style,
+    pub preview: Style,
+}
+
+impl Palette {
+    const DARK: Palette = Palette {
+        selected: Style::new().fg(Color::Red),
+        matched: Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+        marker: Style::new().fg(Color::Yellow),
+        border: Style::new(),
+        prompt: Style::new(),
+        status: Style::new(),
+        preview: Style::new(),
+    };
+
+    const LIGHT: Palette = Palette {
+        selected: Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+        matched: Style::new().fg(Color::Blue).add_modifier(Modifier::BOLD),
+        marker: Style::new().fg(Color::Magenta),
+        border: Style::new().fg(Color::Gray),
+        prompt: Style::new(),
+        status: Style::new().fg(Color::DarkGray),
+        preview: Style::new(),
+    };
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        ThemePreset::default().palette()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let strings = ["dark", "light", "invalid"];
+        let parsed_strings = strings.map(|s| toml::Value::String(s.to_string()).try_into().ok());
+
+        assert_eq!(
+            parsed_strings,
+            [Some(ThemePreset::Dark), Some(ThemePreset::Light), None]
+        );
+    }
+}
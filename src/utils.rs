@@ -1,4 +1,97 @@
-use std::ops::Deref;
+use std::{env, ffi::OsString, fs::File, io, ops::Deref};
+
+use tokio::process::Command;
+
+/// Returns the user's shell, falling back to `sh` if `$SHELL` isn't set.
+pub fn shell() -> OsString {
+    env::var_os("SHELL").unwrap_or_else(|| "sh".into())
+}
+
+/// Creates a command that's run by the user's shell in its own process group.
+pub fn shell_command(command: &str) -> Command {
+    let mut shell_command = Command::new(shell());
+    shell_command
+        .arg("-c")
+        .arg(command)
+        .process_group(0)
+        .kill_on_drop(true);
+    shell_command
+}
+
+/// Creates a command that's run by the user's shell in the foreground. It
+/// reads from the terminal, since stdin may be where items are read from.
+pub fn foreground_command(command: &str) -> io::Result<Command> {
+    let mut foreground_command = Command::new(shell());
+    foreground_command
+        .arg("-c")
+        .arg(command)
+        .stdin(File::open("/dev/tty")?);
+    Ok(foreground_command)
+}
+
+/// Quotes a string so the shell interprets it as a single word.
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Splits a string on whitespace that isn't escaped with a backslash.
+pub fn split_words(s: &str) -> impl Iterator<Item = &str> {
+    let mut escaped = false;
+    s.split(move |c: char| {
+        let split = c.is_whitespace() && !escaped;
+        escaped = c == '\\' && !escaped;
+        split
+    })
+    .filter(|word| !word.is_empty())
+}
+
+/// Replaces the escape sequences `\0`, `\t`, `\n`, `\r` and `\\` with the
+/// characters they stand for. Other backslashes are kept.
+pub fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('0') => unescaped.push('\0'),
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') => unescaped.push('\\'),
+            Some(c) => unescaped.extend(['\\', c]),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Terminates a process group when dropped, so processes started by a shell
+/// don't outlive tez.
+pub struct ProcessGroup(Option<u32>);
+
+impl ProcessGroup {
+    pub fn new(id: Option<u32>) -> Self {
+        ProcessGroup(id)
+    }
+
+    /// Keeps the process group from being terminated, which should be done
+    /// once its leader has been waited on.
+    pub fn release(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        if let Some(pid) = self.0 {
+            // SAFETY: kill has no memory safety requirements
+            unsafe { libc::kill(-(pid as i32), libc::SIGTERM) };
+        }
+    }
+}
 
 pub trait StrExt {
     /// Finds the last occurrence of a char within the first group, where a
@@ -34,4 +127,25 @@ mod tests {
         assert_eq!("baa".find_last_adjacent('a'), Some(2));
         assert_eq!("aa aa".find_last_adjacent('a'), Some(4));
     }
+
+    #[test]
+    fn split_words() {
+        assert_eq!(
+            super::split_words(" a  b\\ c\\\\ d ").collect::<Vec<_>>(),
+            ["a", "b\\ c\\\\", "d"]
+        );
+    }
+
+    #[test]
+    fn unescape() {
+        assert_eq!(super::unescape(r"a\0b\tc\nd"), "a\0b\tc\nd");
+        assert_eq!(super::unescape(r"\\n \x \"), "\\n \\x \\");
+    }
+
+    #[test]
+    fn shell_quote() {
+        assert_eq!(super::shell_quote(""), "''");
+        assert_eq!(super::shell_quote("a b"), "'a b'");
+        assert_eq!(super::shell_quote("it's"), r"'it'\''s'");
+    }
 }